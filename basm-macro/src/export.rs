@@ -42,15 +42,17 @@ pub fn export_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
         mod #basm_export_mod {
             #[allow(non_snake_case)]
             mod #internals {
-                pub static mut SER_VEC: alloc::vec::Vec::<u8> = alloc::vec::Vec::<u8>::new();
+                extern crate basm_std;
+                pub static SER_VEC: basm_std::platform::Global<alloc::vec::Vec::<u8>> =
+                    basm_std::platform::Global::new(alloc::vec::Vec::<u8>::new());
 
                 #[cfg(target_arch = "x86_64")]
                 #[inline(never)]
-                pub unsafe extern "win64" fn free() { SER_VEC.clear() }
+                pub unsafe extern "win64" fn free() { SER_VEC.with(|x| x.clear()) }
 
                 #[cfg(not(target_arch = "x86_64"))]
                 #[inline(never)]
-                pub unsafe extern "C" fn free() { SER_VEC.clear() }
+                pub unsafe extern "C" fn free() { SER_VEC.with(|x| x.clear()) }
 
                 #[cfg(target_arch = "x86_64")]
                 #[unsafe(no_mangle)]
@@ -79,10 +81,12 @@ pub fn export_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 basm_std::serialization::call_free(ptr_free_remote);
                 let out = super::#fn_name(#( #arg_borrows #arg_names_anonymous ),*);
 
-                assert!(#internals::SER_VEC.is_empty());
-                out.ser_len(&mut #internals::SER_VEC, 0);
-                (#internals::free as usize).ser_len(&mut #internals::SER_VEC, 0);
-                #internals::SER_VEC.as_ptr() as usize
+                #internals::SER_VEC.with(|ser_vec| {
+                    assert!(ser_vec.is_empty());
+                    out.ser_len(ser_vec, 0);
+                    (#internals::free as usize).ser_len(ser_vec, 0);
+                    ser_vec.as_ptr() as usize
+                })
             }
         }
     };
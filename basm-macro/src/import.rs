@@ -47,26 +47,28 @@ fn import_impl_single(sig: &Signature) -> TokenStream {
         mod #basm_import_mod {
             #[allow(non_snake_case)]
             mod #internals {
-                pub static mut SER_VEC: alloc::vec::Vec::<u8> = alloc::vec::Vec::<u8>::new();
-                pub static mut PTR_FN: usize = 0;
+                extern crate basm_std;
+                pub static SER_VEC: basm_std::platform::Global<alloc::vec::Vec::<u8>> =
+                    basm_std::platform::Global::new(alloc::vec::Vec::<u8>::new());
+                pub static PTR_FN: basm_std::platform::Global<usize> = basm_std::platform::Global::new(0);
 
                 #[cfg(target_arch = "x86_64")]
                 #[inline(never)]
-                pub unsafe extern "win64" fn free() { SER_VEC.clear() }
+                pub unsafe extern "win64" fn free() { SER_VEC.with(|x| x.clear()) }
 
                 #[cfg(not(target_arch = "x86_64"))]
                 #[inline(never)]
-                pub unsafe extern "C" fn free() { SER_VEC.clear() }
+                pub unsafe extern "C" fn free() { SER_VEC.with(|x| x.clear()) }
 
                 #[cfg(target_arch = "x86_64")]
                 #[unsafe(no_mangle)]
                 #[inline(never)]
-                pub unsafe extern "win64" fn #basm_import(ptr_fn: usize) { PTR_FN = ptr_fn; }
+                pub unsafe extern "win64" fn #basm_import(ptr_fn: usize) { PTR_FN.set(ptr_fn); }
 
                 #[cfg(not(target_arch = "x86_64"))]
                 #[unsafe(no_mangle)]
                 #[inline(never)]
-                pub unsafe extern "C" fn #basm_import(ptr_fn: usize) { PTR_FN = ptr_fn; }
+                pub unsafe extern "C" fn #basm_import(ptr_fn: usize) { PTR_FN.set(ptr_fn); }
             }
 
             use super::*;
@@ -75,10 +77,13 @@ fn import_impl_single(sig: &Signature) -> TokenStream {
                 extern crate basm_std;
                 use basm_std::serialization::{Ser, De};
                 unsafe {
-                    assert!(#internals::SER_VEC.is_empty());
-                    #( #arg_names.ser_len(&mut #internals::SER_VEC, 0); )*
-                    (#internals::free as usize).ser_len(&mut #internals::SER_VEC, 0);
-                    let ptr_serialized = basm_std::serialization::call_import(#internals::PTR_FN, #internals::SER_VEC.as_ptr() as usize);
+                    let ptr_ser_vec = #internals::SER_VEC.with(|ser_vec| {
+                        assert!(ser_vec.is_empty());
+                        #( #arg_names.ser_len(ser_vec, 0); )*
+                        (#internals::free as usize).ser_len(ser_vec, 0);
+                        ser_vec.as_ptr() as usize
+                    });
+                    let ptr_serialized = basm_std::serialization::call_import(#internals::PTR_FN.get(), ptr_ser_vec);
 
                     let mut buf: &'static [u8] = basm_std::serialization::eat(ptr_serialized);
                     type ReturnType = #return_type;
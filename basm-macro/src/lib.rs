@@ -5,6 +5,7 @@ extern crate syn;
 
 mod export;
 mod import;
+mod readable;
 mod types;
 mod utils;
 
@@ -23,3 +24,25 @@ pub fn basm_import(item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item);
     import::import_impl(item).into()
 }
+
+/// Derives `basm_std::platform::io::Readable` for a struct.
+///
+/// Fields are read in declaration order. A `Vec`-like field whose length is given
+/// by an earlier field can be annotated with `#[read(len = expr)]`, where `expr`
+/// may refer to earlier fields by name (or `_0`, `_1`, ... in tuple structs).
+/// Fields annotated with `#[read(skip)]` are set to `Default::default()`.
+///
+/// ```ignore
+/// #[derive(Readable)]
+/// struct Query {
+///     kind: u8,
+///     n: usize,
+///     #[read(len = n)]
+///     values: Vec<i64>,
+/// }
+/// let q: Query = reader.next();
+/// ```
+#[proc_macro_derive(Readable, attributes(read))]
+pub fn derive_readable(item: TokenStream) -> TokenStream {
+    readable::derive_readable_impl(item.into()).into()
+}
@@ -0,0 +1,112 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Expr, Fields, GenericArgument, PathArguments, Type};
+
+/// Field-level options given by `#[read(...)]`.
+#[derive(Default)]
+struct FieldAttr {
+    /// `#[read(len = expr)]`: the field is a collection of `expr` elements.
+    len: Option<Expr>,
+    /// `#[read(skip)]`: the field is not read and is set to `Default::default()`.
+    skip: bool,
+}
+
+fn parse_field_attr(attrs: &[syn::Attribute]) -> FieldAttr {
+    let mut out = FieldAttr::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("read")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("len") {
+                out.len = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                out.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported read attribute; expected `len = ...` or `skip`"))
+            }
+        })
+        .unwrap_or_else(|e| panic!("{}", e));
+    }
+    assert!(
+        !(out.skip && out.len.is_some()),
+        "`skip` and `len` cannot be used together"
+    );
+    out
+}
+
+/// Returns `T` if `ty` is of the form `Path<T>` (e.g., `Vec<T>`, `VecDeque<T>`).
+fn element_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    let PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|x| match x {
+        GenericArgument::Type(t) => Some(t),
+        _ => None,
+    });
+    let out = types.next();
+    if types.next().is_some() { None } else { out }
+}
+
+pub fn derive_readable_impl(item: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse2(item).unwrap();
+    let Data::Struct(data) = &input.data else {
+        panic!("#[derive(Readable)] is only supported for structs");
+    };
+
+    let mut reads = vec![];
+    let mut names = vec![];
+    for (i, field) in data.fields.iter().enumerate() {
+        // Tuple struct fields are bound to `_0`, `_1`, ... so that they can be referred to by `len`.
+        let name = field
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("_{}", i));
+        let ty = &field.ty;
+        let attr = parse_field_attr(&field.attrs);
+        let read = if attr.skip {
+            quote! { <#ty as core::default::Default>::default() }
+        } else if let Some(len) = &attr.len {
+            let elem = match element_type(ty) {
+                Some(t) => quote!(#t),
+                None => quote!(_),
+            };
+            quote! {
+                basm_std::platform::io::ReaderTrait::collect::<#ty, #elem>(reader, (#len) as usize)
+            }
+        } else {
+            quote! { <#ty as basm_std::platform::io::Readable>::read(reader) }
+        };
+        reads.push(quote! {
+            #[allow(unused_variables, non_snake_case)]
+            let #name: #ty = #read;
+        });
+        names.push(name);
+    }
+    let construct = match &data.fields {
+        Fields::Named(_) => quote! { Self { #( #names ),* } },
+        Fields::Unnamed(_) => quote! { Self ( #( #names ),* ) },
+        Fields::Unit => quote! { Self },
+    };
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(basm_std::platform::io::Readable));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+    quote! {
+        const _: () = {
+            extern crate basm_std;
+
+            impl #impl_generics basm_std::platform::io::Readable for #ident #ty_generics #where_clause {
+                fn read(reader: &mut impl basm_std::platform::io::ReaderTrait) -> Self {
+                    #( #reads )*
+                    #construct
+                }
+            }
+        };
+    }
+}
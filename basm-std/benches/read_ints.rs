@@ -0,0 +1,48 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+use rand::{Rng, SeedableRng};
+
+use basm_std::platform::io::{ReaderTrait, SliceReader};
+
+const N: usize = 1_000_000;
+
+fn random_ints() -> Vec<u8> {
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(123);
+    let mut text = Vec::new();
+    for i in 0..N {
+        let x: i32 = rng.random();
+        text.extend_from_slice(x.to_string().as_bytes());
+        text.push(if i % 10 == 9 { b'\n' } else { b' ' });
+    }
+    text
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let text = random_ints();
+    let mut out = vec![0i32; N];
+    let mut group = c.benchmark_group("read_i32_1m");
+    group.bench_function("i32", |b| {
+        b.iter(|| {
+            let mut reader = SliceReader::new(black_box(&text));
+            for x in out.iter_mut() {
+                *x = reader.i32();
+            }
+            black_box(&out);
+        })
+    });
+    group.bench_function("read_i32_into", |b| {
+        b.iter(|| {
+            let mut reader = SliceReader::new(black_box(&text));
+            reader.read_i32_into(&mut out);
+            black_box(&out);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().significance_level(0.1).sample_size(10);
+    targets = criterion_benchmark
+);
+criterion_main!(benches);
@@ -12,3 +12,9 @@ pub use fenwick::*;
 
 pub mod segment_tree;
 pub use segment_tree::*;
+
+pub mod trie;
+pub use trie::*;
+
+pub mod xor_trie;
+pub use xor_trie::*;
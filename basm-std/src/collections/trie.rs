@@ -0,0 +1,294 @@
+use alloc::{vec, vec::Vec};
+
+const NIL: u32 = u32::MAX;
+
+#[derive(Clone)]
+struct Node {
+    child: u32,
+    sibling: u32,
+    byte: u8,
+    pass: u32,
+    end: u32,
+}
+
+impl Node {
+    const fn new(byte: u8) -> Self {
+        Self {
+            child: NIL,
+            sibling: NIL,
+            byte,
+            pass: 0,
+            end: 0,
+        }
+    }
+}
+
+/// Trie over byte strings (multiset semantics).
+///
+/// All nodes live in a single `Vec` and refer to each other by `u32` indices,
+/// so that no per-node allocation takes place. Children of a node are kept
+/// in a singly linked list sorted by byte, which keeps the memory usage
+/// proportional to the total length of the inserted keys.
+pub struct Trie {
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+    /// Creates an empty `Trie`.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(0)],
+        }
+    }
+
+    /// Creates an empty `Trie` with space for at least `capacity` nodes.
+    ///
+    /// The total length of the keys to be inserted is a good estimate for `capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut nodes = Vec::with_capacity(capacity + 1);
+        nodes.push(Node::new(0));
+        Self { nodes }
+    }
+
+    /// Returns the number of keys in the current instance, counting multiplicity.
+    pub fn len(&self) -> usize {
+        self.nodes[0].pass as usize
+    }
+
+    /// Returns `true` if the current instance contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.nodes[0].pass == 0
+    }
+
+    /// Returns the number of allocated nodes, including the root.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Removes all keys. The allocated memory is retained.
+    pub fn clear(&mut self) {
+        self.nodes.truncate(1);
+        self.nodes[0] = Node::new(0);
+    }
+
+    fn find_child(&self, u: usize, byte: u8) -> Option<usize> {
+        let mut v = self.nodes[u].child;
+        while v != NIL {
+            let node = &self.nodes[v as usize];
+            if node.byte >= byte {
+                return (node.byte == byte).then_some(v as usize);
+            }
+            v = node.sibling;
+        }
+        None
+    }
+
+    fn find_or_insert_child(&mut self, u: usize, byte: u8) -> usize {
+        let mut prev = NIL;
+        let mut v = self.nodes[u].child;
+        while v != NIL {
+            let node = &self.nodes[v as usize];
+            if node.byte == byte {
+                return v as usize;
+            } else if node.byte > byte {
+                break;
+            }
+            prev = v;
+            v = node.sibling;
+        }
+        let id = self.nodes.len() as u32;
+        let mut node = Node::new(byte);
+        node.sibling = v;
+        self.nodes.push(node);
+        if prev == NIL {
+            self.nodes[u].child = id;
+        } else {
+            self.nodes[prev as usize].sibling = id;
+        }
+        id as usize
+    }
+
+    fn find(&self, key: &[u8]) -> Option<usize> {
+        let mut u = 0;
+        for &b in key {
+            u = self.find_child(u, b)?;
+        }
+        Some(u)
+    }
+
+    /// Inserts `key` once.
+    pub fn insert(&mut self, key: &[u8]) {
+        let mut u = 0;
+        self.nodes[u].pass += 1;
+        for &b in key {
+            u = self.find_or_insert_child(u, b);
+            self.nodes[u].pass += 1;
+        }
+        self.nodes[u].end += 1;
+    }
+
+    /// Removes one occurrence of `key`.
+    ///
+    /// Returns `true` if `key` was present, `false` otherwise.
+    /// Nodes are not freed; they are reused if the same prefix is inserted again.
+    pub fn remove(&mut self, key: &[u8]) -> bool {
+        match self.find(key) {
+            Some(u) if self.nodes[u].end > 0 => {
+                self.nodes[u].end -= 1;
+                let mut u = 0;
+                self.nodes[u].pass -= 1;
+                for &b in key {
+                    u = self.find_child(u, b).unwrap();
+                    self.nodes[u].pass -= 1;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the number of occurrences of `key`.
+    pub fn count(&self, key: &[u8]) -> usize {
+        self.find(key).map_or(0, |u| self.nodes[u].end as usize)
+    }
+
+    /// Returns `true` if `key` occurs at least once.
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.count(key) > 0
+    }
+
+    /// Returns the number of keys (counting multiplicity) that start with `prefix`.
+    pub fn count_prefix(&self, prefix: &[u8]) -> usize {
+        self.find(prefix).map_or(0, |u| self.nodes[u].pass as usize)
+    }
+
+    /// Returns the number of keys (counting multiplicity) that are lexicographically smaller than `key`.
+    pub fn rank(&self, key: &[u8]) -> usize {
+        let mut out = 0;
+        let mut u = 0;
+        for &b in key {
+            out += self.nodes[u].end as usize;
+            let mut v = self.nodes[u].child;
+            loop {
+                if v == NIL {
+                    return out;
+                }
+                let node = &self.nodes[v as usize];
+                if node.byte >= b {
+                    if node.byte > b {
+                        return out;
+                    }
+                    break;
+                }
+                out += node.pass as usize;
+                v = node.sibling;
+            }
+            u = v as usize;
+        }
+        out
+    }
+
+    /// Returns the `k`-th (0-indexed) smallest key in lexicographic order, counting multiplicity.
+    ///
+    /// Returns `None` if `k >= self.len()`.
+    pub fn kth(&self, mut k: usize) -> Option<Vec<u8>> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut out = vec![];
+        let mut u = 0;
+        loop {
+            let end = self.nodes[u].end as usize;
+            if k < end {
+                break Some(out);
+            }
+            k -= end;
+            let mut v = self.nodes[u].child;
+            loop {
+                let node = &self.nodes[v as usize];
+                if k < node.pass as usize {
+                    break;
+                }
+                k -= node.pass as usize;
+                v = node.sibling;
+            }
+            u = v as usize;
+            out.push(self.nodes[u].byte);
+        }
+    }
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_count_remove() {
+        let mut trie = Trie::new();
+        trie.insert(b"apple");
+        trie.insert(b"app");
+        trie.insert(b"apple");
+        trie.insert(b"banana");
+        assert_eq!(4, trie.len());
+        assert_eq!(2, trie.count(b"apple"));
+        assert_eq!(1, trie.count(b"app"));
+        assert_eq!(0, trie.count(b"ap"));
+        assert_eq!(3, trie.count_prefix(b"ap"));
+        assert_eq!(4, trie.count_prefix(b""));
+        assert_eq!(0, trie.count_prefix(b"c"));
+
+        assert!(trie.remove(b"apple"));
+        assert!(!trie.remove(b"ap"));
+        assert!(!trie.remove(b"cherry"));
+        assert_eq!(1, trie.count(b"apple"));
+        assert_eq!(2, trie.count_prefix(b"app"));
+        assert_eq!(3, trie.len());
+    }
+
+    #[test]
+    fn empty_key() {
+        let mut trie = Trie::new();
+        trie.insert(b"");
+        trie.insert(b"a");
+        assert_eq!(1, trie.count(b""));
+        assert_eq!(Some(vec![]), trie.kth(0));
+        assert_eq!(Some(b"a".to_vec()), trie.kth(1));
+        assert!(trie.remove(b""));
+        assert_eq!(Some(b"a".to_vec()), trie.kth(0));
+    }
+
+    #[test]
+    fn kth_and_rank_match_sorted_order() {
+        let words: [&[u8]; 9] = [b"b", b"ab", b"abc", b"a", b"zz", b"ab", b"\xff", b"ba", b""];
+        let mut trie = Trie::new();
+        for w in words {
+            trie.insert(w);
+        }
+        let mut sorted = words.to_vec();
+        sorted.sort();
+        for (k, w) in sorted.iter().enumerate() {
+            assert_eq!(Some(w.to_vec()), trie.kth(k));
+            assert_eq!(sorted.partition_point(|x| x < w), trie.rank(w));
+        }
+        assert_eq!(None, trie.kth(words.len()));
+        assert_eq!(2, trie.rank(b"aa"));
+        assert_eq!(words.len(), trie.rank(b"\xff\x00"));
+    }
+
+    #[test]
+    fn clear_keeps_root() {
+        let mut trie = Trie::with_capacity(16);
+        trie.insert(b"hello");
+        trie.clear();
+        assert!(trie.is_empty());
+        assert_eq!(1, trie.node_count());
+        trie.insert(b"world");
+        assert_eq!(1, trie.count(b"world"));
+    }
+}
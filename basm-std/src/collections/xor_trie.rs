@@ -0,0 +1,246 @@
+use alloc::{vec, vec::Vec};
+
+/// Binary trie over the lowest `BITS` bits of `u64` values (multiset semantics).
+///
+/// Nodes are stored in a single `Vec` and refer to each other by `u32` indices.
+/// Since the root (index 0) is never a child, 0 is used as the null link.
+///
+/// Only the lowest `BITS` bits of each value are stored; the higher bits of
+/// arguments passed to the methods are ignored.
+pub struct XorTrie<const BITS: u32> {
+    next: Vec<[u32; 2]>,
+    cnt: Vec<u32>,
+}
+
+impl<const BITS: u32> XorTrie<BITS> {
+    const DUMMY: () = assert!(
+        BITS >= 1 && BITS <= 64,
+        "BITS for XorTrie must be between 1 and 64"
+    );
+    const MASK: u64 = u64::MAX >> (64 - BITS);
+
+    /// Creates an empty `XorTrie`.
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::DUMMY;
+        Self {
+            next: vec![[0; 2]],
+            cnt: vec![0],
+        }
+    }
+
+    /// Creates an empty `XorTrie` with space for `n` values.
+    ///
+    /// This reserves `n * BITS + 1` nodes, which suffices for `n` insertions.
+    pub fn with_capacity(n: usize) -> Self {
+        let mut out = Self::new();
+        out.next.reserve(n * BITS as usize);
+        out.cnt.reserve(n * BITS as usize);
+        out
+    }
+
+    /// Returns the number of values in the current instance, counting multiplicity.
+    pub fn len(&self) -> usize {
+        self.cnt[0] as usize
+    }
+
+    /// Returns `true` if the current instance contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.cnt[0] == 0
+    }
+
+    /// Removes all values. The allocated memory is retained.
+    pub fn clear(&mut self) {
+        self.next.truncate(1);
+        self.cnt.truncate(1);
+        self.next[0] = [0; 2];
+        self.cnt[0] = 0;
+    }
+
+    /// Inserts `x` once.
+    pub fn insert(&mut self, x: u64) {
+        let mut u = 0;
+        self.cnt[u] += 1;
+        for i in (0..BITS).rev() {
+            let b = (x >> i) as usize & 1;
+            if self.next[u][b] == 0 {
+                self.next[u][b] = self.next.len() as u32;
+                self.next.push([0; 2]);
+                self.cnt.push(0);
+            }
+            u = self.next[u][b] as usize;
+            self.cnt[u] += 1;
+        }
+    }
+
+    /// Removes one occurrence of `x`.
+    ///
+    /// Returns `true` if `x` was present, `false` otherwise.
+    pub fn remove(&mut self, x: u64) -> bool {
+        if self.count(x) == 0 {
+            return false;
+        }
+        let mut u = 0;
+        self.cnt[u] -= 1;
+        for i in (0..BITS).rev() {
+            u = self.next[u][(x >> i) as usize & 1] as usize;
+            self.cnt[u] -= 1;
+        }
+        true
+    }
+
+    /// Returns the number of occurrences of `x`.
+    pub fn count(&self, x: u64) -> usize {
+        let mut u = 0;
+        for i in (0..BITS).rev() {
+            u = self.next[u][(x >> i) as usize & 1] as usize;
+            if u == 0 {
+                return 0;
+            }
+        }
+        self.cnt[u] as usize
+    }
+
+    /// Returns `true` if `x` occurs at least once.
+    pub fn contains(&self, x: u64) -> bool {
+        self.count(x) > 0
+    }
+
+    fn child(&self, u: usize, b: usize) -> usize {
+        let v = self.next[u][b] as usize;
+        if v != 0 && self.cnt[v] > 0 { v } else { 0 }
+    }
+
+    // Walks down the trie, preferring the child whose bit equals `(x >> i) & 1` xor `flip`.
+    // Returns the value `v` found at the leaf.
+    fn walk(&self, x: u64, flip: usize) -> Option<u64> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut u = 0;
+        let mut v = 0u64;
+        for i in (0..BITS).rev() {
+            let want = ((x >> i) as usize & 1) ^ flip;
+            let w = self.child(u, want);
+            let b = if w != 0 {
+                u = w;
+                want
+            } else {
+                u = self.child(u, want ^ 1);
+                want ^ 1
+            };
+            v |= (b as u64) << i;
+        }
+        Some(v)
+    }
+
+    /// Returns the maximum of `x ^ v` over all values `v` in the current instance.
+    ///
+    /// Returns `None` if the current instance is empty.
+    pub fn max_xor(&self, x: u64) -> Option<u64> {
+        self.walk(x, 1).map(|v| (v ^ x) & Self::MASK)
+    }
+
+    /// Returns the minimum of `x ^ v` over all values `v` in the current instance.
+    ///
+    /// Returns `None` if the current instance is empty.
+    pub fn min_xor(&self, x: u64) -> Option<u64> {
+        self.walk(x, 0).map(|v| (v ^ x) & Self::MASK)
+    }
+
+    /// Returns the number of values `v` (counting multiplicity) such that `x ^ v < k`.
+    ///
+    /// Both `x` and `k` are truncated to the lowest `BITS` bits.
+    pub fn count_xor_less(&self, x: u64, k: u64) -> usize {
+        let mut out = 0;
+        let mut u = 0;
+        for i in (0..BITS).rev() {
+            let xb = (x >> i) as usize & 1;
+            if (k >> i) & 1 != 0 {
+                // Values with bit (xb) here make the xor bit 0 < 1, so all of them count.
+                let same = self.next[u][xb] as usize;
+                if same != 0 {
+                    out += self.cnt[same] as usize;
+                }
+                u = self.next[u][xb ^ 1] as usize;
+            } else {
+                u = self.next[u][xb] as usize;
+            }
+            if u == 0 {
+                break;
+            }
+        }
+        out
+    }
+
+    /// Returns the smallest value in the current instance, or `None` if it is empty.
+    pub fn min(&self) -> Option<u64> {
+        self.walk(0, 0)
+    }
+
+    /// Returns the largest value in the current instance, or `None` if it is empty.
+    pub fn max(&self) -> Option<u64> {
+        self.walk(Self::MASK, 0)
+    }
+}
+
+impl<const BITS: u32> Default for XorTrie<BITS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn max_min_xor() {
+        let mut trie = XorTrie::<4>::new();
+        assert_eq!(None, trie.max_xor(0));
+        for x in [3, 10, 5, 5] {
+            trie.insert(x);
+        }
+        assert_eq!(Some(15), trie.max_xor(5));
+        assert_eq!(Some(0), trie.min_xor(5));
+        assert_eq!(Some(1), trie.min_xor(4));
+        assert_eq!(Some(3), trie.min());
+        assert_eq!(Some(10), trie.max());
+        assert!(trie.remove(5));
+        assert_eq!(Some(0), trie.min_xor(5));
+        assert!(trie.remove(5));
+        assert!(!trie.remove(5));
+        assert_eq!(Some(6), trie.min_xor(5));
+        assert_eq!(2, trie.len());
+    }
+
+    #[test]
+    fn count_xor_less_matches_naive() {
+        let values = [0u64, 1, 7, 7, 12, 31, 18, 25, 9];
+        let mut trie = XorTrie::<5>::with_capacity(values.len());
+        for &v in &values {
+            trie.insert(v);
+        }
+        for x in 0..32 {
+            for k in 0..32 {
+                let naive = values.iter().filter(|&&v| (x ^ v) < k).count();
+                assert_eq!(naive, trie.count_xor_less(x, k));
+            }
+            let naive_max = values.iter().map(|&v| x ^ v).max();
+            assert_eq!(naive_max, trie.max_xor(x));
+        }
+    }
+
+    #[test]
+    fn full_width() {
+        let mut trie = XorTrie::<64>::new();
+        trie.insert(u64::MAX);
+        trie.insert(0);
+        assert_eq!(Some(u64::MAX), trie.max_xor(0));
+        assert_eq!(Some(0), trie.min_xor(u64::MAX));
+        assert_eq!(1, trie.count_xor_less(0, u64::MAX));
+        trie.clear();
+        assert!(trie.is_empty());
+        assert_eq!(None, trie.min());
+    }
+}
@@ -16,6 +16,7 @@ pub mod collections;
 pub mod graph;
 pub mod math;
 pub mod platform;
+pub mod random;
 pub mod serialization;
 pub mod sorts;
 pub mod strings;
@@ -1,22 +1,81 @@
+use super::Global;
 use core::alloc::GlobalAlloc;
 
-static mut PTR_ALLOC: unsafe fn(usize, usize) -> *mut u8 = super::services::alloc;
-static mut PTR_ALLOC_ZEROED: unsafe fn(usize, usize) -> *mut u8 = super::services::alloc_zeroed;
-static mut PTR_DEALLOC: unsafe fn(*mut u8, usize, usize) = super::services::dealloc;
-static mut PTR_REALLOC: unsafe fn(*mut u8, usize, usize, usize) -> *mut u8 =
-    super::services::realloc;
+type AllocFn = unsafe fn(usize, usize) -> *mut u8;
+type DeallocFn = unsafe fn(*mut u8, usize, usize);
+type ReallocFn = unsafe fn(*mut u8, usize, usize, usize) -> *mut u8;
+
+static PTR_ALLOC: Global<AllocFn> = Global::new(super::services::alloc);
+static PTR_ALLOC_ZEROED: Global<AllocFn> = Global::new(super::services::alloc_zeroed);
+static PTR_DEALLOC: Global<DeallocFn> = Global::new(super::services::dealloc);
+static PTR_REALLOC: Global<ReallocFn> = Global::new(super::services::realloc);
 
 pub unsafe fn install_malloc_impl(
-    ptr_alloc: unsafe fn(usize, usize) -> *mut u8,
-    ptr_alloc_zeroed: unsafe fn(usize, usize) -> *mut u8,
-    ptr_dealloc: unsafe fn(*mut u8, usize, usize),
-    ptr_realloc: unsafe fn(*mut u8, usize, usize, usize) -> *mut u8,
+    ptr_alloc: AllocFn,
+    ptr_alloc_zeroed: AllocFn,
+    ptr_dealloc: DeallocFn,
+    ptr_realloc: ReallocFn,
 ) {
-    unsafe {
-        PTR_ALLOC = ptr_alloc;
-        PTR_ALLOC_ZEROED = ptr_alloc_zeroed;
-        PTR_DEALLOC = ptr_dealloc;
-        PTR_REALLOC = ptr_realloc;
+    PTR_ALLOC.set(ptr_alloc);
+    PTR_ALLOC_ZEROED.set(ptr_alloc_zeroed);
+    PTR_DEALLOC.set(ptr_dealloc);
+    PTR_REALLOC.set(ptr_realloc);
+}
+
+/// Heap usage statistics, collected when the `alloc-stats` feature is enabled (except under `submit`).
+#[cfg(all(feature = "alloc-stats", not(feature = "submit")))]
+pub mod stats {
+    use crate::platform::Global;
+    use crate::platform::io::{ErrWriter, Fixed, Print};
+
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct AllocStats {
+        /// Bytes currently allocated.
+        pub current: usize,
+        /// Maximum of `current` so far.
+        pub peak: usize,
+        /// Number of allocations (including reallocations).
+        pub count: usize,
+        /// Size of the largest single allocation.
+        pub largest: usize,
+    }
+
+    static STATS: Global<AllocStats> = Global::new(AllocStats {
+        current: 0,
+        peak: 0,
+        count: 0,
+        largest: 0,
+    });
+
+    /// Returns the statistics so far.
+    pub fn get() -> AllocStats {
+        STATS.get()
+    }
+    pub(super) fn on_alloc(size: usize) {
+        STATS.with(|stats| {
+            stats.current += size;
+            stats.peak = stats.peak.max(stats.current);
+            stats.count += 1;
+            stats.largest = stats.largest.max(size);
+        });
+    }
+    pub(super) fn on_dealloc(size: usize) {
+        STATS.with(|stats| stats.current -= size);
+    }
+    /// Writes the statistics to standard error. Note that the stack and static data are not included.
+    pub fn report() {
+        let stats = get();
+        let mib = |bytes: usize| Fixed(bytes as f64 / (1024.0 * 1024.0), 2);
+        let mut err = ErrWriter::<256>::new();
+        err.print("[alloc-stats] peak ");
+        err.print(mib(stats.peak));
+        err.print(" MiB, current ");
+        err.print(mib(stats.current));
+        err.print(" MiB, largest ");
+        err.print(mib(stats.largest));
+        err.print(" MiB, ");
+        err.print(stats.count);
+        err.println(" allocations");
     }
 }
 
@@ -25,15 +84,27 @@ pub struct Allocator;
 unsafe impl GlobalAlloc for Allocator {
     #[inline(always)]
     unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
-        unsafe { PTR_ALLOC(layout.size(), layout.align()) }
+        let ptr = unsafe { PTR_ALLOC.get()(layout.size(), layout.align()) };
+        #[cfg(all(feature = "alloc-stats", not(feature = "submit")))]
+        if !ptr.is_null() {
+            stats::on_alloc(layout.size());
+        }
+        ptr
     }
     #[inline(always)]
     unsafe fn alloc_zeroed(&self, layout: core::alloc::Layout) -> *mut u8 {
-        unsafe { PTR_ALLOC_ZEROED(layout.size(), layout.align()) }
+        let ptr = unsafe { PTR_ALLOC_ZEROED.get()(layout.size(), layout.align()) };
+        #[cfg(all(feature = "alloc-stats", not(feature = "submit")))]
+        if !ptr.is_null() {
+            stats::on_alloc(layout.size());
+        }
+        ptr
     }
     #[inline(always)]
     unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
-        unsafe { PTR_DEALLOC(ptr, layout.size(), layout.align()) }
+        #[cfg(all(feature = "alloc-stats", not(feature = "submit")))]
+        stats::on_dealloc(layout.size());
+        unsafe { PTR_DEALLOC.get()(ptr, layout.size(), layout.align()) }
     }
     #[inline(always)]
     unsafe fn realloc(
@@ -42,6 +113,12 @@ unsafe impl GlobalAlloc for Allocator {
         layout: core::alloc::Layout,
         new_size: usize,
     ) -> *mut u8 {
-        unsafe { PTR_REALLOC(ptr, layout.size(), layout.align(), new_size) }
+        let new_ptr = unsafe { PTR_REALLOC.get()(ptr, layout.size(), layout.align(), new_size) };
+        #[cfg(all(feature = "alloc-stats", not(feature = "submit")))]
+        if !new_ptr.is_null() {
+            stats::on_dealloc(layout.size());
+            stats::on_alloc(new_size);
+        }
+        new_ptr
     }
 }
@@ -60,6 +60,7 @@ macro_rules! define_basm_start {
                 target_arch = "x86_64",
                 target_arch = "x86",
                 target_arch = "aarch64",
+                target_arch = "riscv64",
                 target_arch = "wasm32"
             )))]
             compile_error!("The target architecture is not supported.");
@@ -69,6 +70,11 @@ macro_rules! define_basm_start {
                 "AArch64 (aarch64-apple-darwin) is only supported for local execution, not submission; use x86-64 to submit."
             );
 
+            #[cfg(all(target_arch = "riscv64", feature = "submit"))]
+            compile_error!(
+                "RISC-V (riscv64gc-unknown-linux-gnu) is only supported for local execution, not submission; use x86-64 to submit."
+            );
+
             #[cfg(all(target_arch = "x86_64", not(target_os = "windows")))]
             #[unsafe(no_mangle)]
             #[unsafe(naked)]
@@ -306,6 +312,28 @@ macro_rules! define_basm_start {
                 )
             }
 
+            #[cfg(all(target_os = "linux", target_arch = "riscv64"))]
+            #[unsafe(no_mangle)]
+            #[unsafe(naked)]
+            pub unsafe extern "C" fn _basm_start() -> ! {
+                // RISC-V psABI requires sp to be aligned on the 16-byte boundary,
+                //   which is already the case at the entrypoint.
+                naked_asm!(
+                    "lla    a0, __ehdr_start",
+                    "lla    a1, _DYNAMIC",
+                    "call   {0}",
+                    "addi   sp, sp, -96",
+                    "li     a0, 2",     // 2 = ENV_ID_LINUX
+                    "sd     a0, 0(sp)",
+                    "li     a0, 2",     // 2 = ENV_FLAGS_NATIVE
+                    "sd     a0, 8(sp)",
+                    "mv     a0, sp",
+                    "call   {1}",
+                    sym loader::riscv64_elf::relocate,
+                    sym _start_rust
+                )
+            }
+
             fn _start_rust(platform_data: usize) -> i32 {
                 platform::init(platform_data);
                 super::_call_main();
@@ -347,7 +375,41 @@ pub unsafe fn print_panicinfo_and_exit(_pi: &core::panic::PanicInfo) -> ! {
         use alloc::string::ToString;
         write_stdio(2, _pi.to_string().as_bytes());
         write_stdio(2, b"\n");
+        exit_panicked()
+    }
+}
 
+/// Exits on panic for `submit` builds with the `submit-panic` feature.
+///
+/// Unlike [`print_panicinfo_and_exit`], the panic message is not formatted, since `core::fmt`
+/// would noticeably increase the code size. With `submit-panic-line`, only the line number is
+/// written to stderr (e.g., `panicked at line 42`).
+#[cfg(feature = "submit-panic")]
+pub unsafe fn exit_on_panic(_pi: &core::panic::PanicInfo) -> ! {
+    unsafe {
+        #[cfg(feature = "submit-panic-line")]
+        if let Some(loc) = _pi.location() {
+            let mut buf = *b"panicked at line 0000000000\n";
+            let mut pos = buf.len() - 1;
+            let mut line = loc.line();
+            loop {
+                pos -= 1;
+                buf[pos] = b'0' + (line % 10) as u8;
+                line /= 10;
+                if line == 0 {
+                    break;
+                }
+            }
+            let prefix = b"panicked at line ".len();
+            buf.copy_within(pos.., prefix);
+            crate::platform::services::write_stdio(2, &buf[..prefix + buf.len() - pos]);
+        }
+        exit_panicked()
+    }
+}
+
+unsafe fn exit_panicked() -> ! {
+    unsafe {
         // Rust sets an exit code of 101 when the process panicked.
         // Hence, we follow that practice for maximum compatibility.
         // Reference: https://rust-cli.github.io/book/in-depth/exit-code.html
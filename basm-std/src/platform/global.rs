@@ -0,0 +1,206 @@
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+
+const UNINIT: u8 = 0;
+const READY: u8 = 1;
+const BORROWED: u8 = 2;
+
+/// A mutable global variable for single-threaded code, to be used in place of `static mut`.
+///
+/// Since the runtime is single-threaded, a `Global` can be placed in a `static` and accessed safely.
+/// Nested access through [`Global::with`] (e.g., from a recursive call inside the closure) panics
+/// instead of creating aliasing mutable references. This also keeps state across calls
+/// of functions exported with `#[basm_export]`, such as memoization tables.
+/// ```
+/// use basm_std::platform::Global;
+/// use std::collections::BTreeMap;
+/// static CALLS: Global<u32> = Global::new(0);
+/// static MEMO: Global<BTreeMap<u64, u64>> = Global::lazy(BTreeMap::new);
+/// fn fib(n: u64) -> u64 {
+///     CALLS.set(CALLS.get() + 1);
+///     if let Some(x) = MEMO.with(|m| m.get(&n).copied()) {
+///         return x;
+///     }
+///     let x = if n < 2 { n } else { fib(n - 1) + fib(n - 2) };
+///     MEMO.with(|m| m.insert(n, x));
+///     x
+/// }
+/// assert_eq!(fib(80), 23416728348467685);
+/// assert_eq!(CALLS.get(), 159);
+/// ```
+///
+/// Note that `Global` must not be shared between threads, which is only possible in tests.
+/// In tests of this crate, accessing a `Global` from a thread other than the first one to access it panics.
+pub struct Global<T> {
+    state: Cell<u8>,
+    #[cfg(test)]
+    owner: std::sync::OnceLock<std::thread::ThreadId>,
+    init: Option<fn() -> T>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: the runtime is single-threaded. In tests, where threads exist, every access is checked
+// to come from the owning thread. `T: Send` is still required, since the value may be dropped
+// on another thread (e.g., the main thread dropping a `static`).
+unsafe impl<T: Send> Sync for Global<T> {}
+
+impl<T> Global<T> {
+    /// Creates a new `Global` holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: Cell::new(READY),
+            #[cfg(test)]
+            owner: std::sync::OnceLock::new(),
+            init: None,
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+        }
+    }
+    /// Creates a new `Global` that is initialized with `init` on first access.
+    pub const fn lazy(init: fn() -> T) -> Self {
+        Self {
+            state: Cell::new(UNINIT),
+            #[cfg(test)]
+            owner: std::sync::OnceLock::new(),
+            init: Some(init),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+    #[cfg(test)]
+    fn check_thread(&self) {
+        let current = std::thread::current().id();
+        assert!(
+            *self.owner.get_or_init(|| current) == current,
+            "Global is accessed from multiple threads"
+        );
+    }
+    #[cfg(not(test))]
+    #[inline(always)]
+    fn check_thread(&self) {}
+    #[inline(always)]
+    fn ensure_ready(&self) {
+        self.check_thread();
+        if self.state.get() != READY {
+            self.initialize();
+        }
+    }
+    // Inlined nonetheless, since passing `&self` to an outlined function would keep LLVM from
+    // optimizing the `static` (e.g., removing function pointers that are never called).
+    #[cold]
+    #[inline(always)]
+    fn initialize(&self) {
+        assert!(self.state.get() == UNINIT, "Global is already borrowed");
+        let Some(init) = self.init else {
+            unreachable!()
+        };
+        self.state.set(BORROWED);
+        let value = init();
+        unsafe {
+            (*self.value.get()).write(value);
+        }
+        self.state.set(READY);
+    }
+    /// Calls `f` with a mutable reference to the value and returns its result.
+    ///
+    /// Panics if called again (for the same `Global`) from within `f`.
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.ensure_ready();
+        self.state.set(BORROWED);
+        let out = f(unsafe { (*self.value.get()).assume_init_mut() });
+        self.state.set(READY);
+        out
+    }
+    /// Replaces the value with `value`, returning the old value.
+    #[inline(always)]
+    pub fn replace(&self, value: T) -> T {
+        self.with(|x| core::mem::replace(x, value))
+    }
+    /// Sets the value to `value`. A lazy `Global` that has not been initialized is not initialized by this.
+    #[inline(always)]
+    pub fn set(&self, value: T) {
+        self.check_thread();
+        match self.state.get() {
+            READY => {
+                drop(unsafe { core::mem::replace((*self.value.get()).assume_init_mut(), value) })
+            }
+            UNINIT => {
+                unsafe {
+                    (*self.value.get()).write(value);
+                }
+                self.state.set(READY);
+            }
+            _ => panic!("Global is already borrowed"),
+        }
+    }
+}
+
+impl<T: Copy> Global<T> {
+    /// Returns a copy of the value.
+    #[inline(always)]
+    pub fn get(&self) -> T {
+        self.ensure_ready();
+        unsafe { (*self.value.get()).assume_init() }
+    }
+}
+
+impl<T> Drop for Global<T> {
+    fn drop(&mut self) {
+        if self.state.get() == READY {
+            unsafe { self.value.get_mut().assume_init_drop() }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+
+    static COUNTER: Global<usize> = Global::new(0);
+
+    #[test]
+    fn global_get_set() {
+        COUNTER.set(COUNTER.get() + 1);
+        COUNTER.with(|x| *x *= 10);
+        assert_eq!(COUNTER.replace(3), 10);
+        assert_eq!(COUNTER.get(), 3);
+
+        let v: Global<Vec<u8>> = Global::lazy(|| vec![1, 2]);
+        v.with(|v| v.push(3));
+        assert_eq!(v.with(|v| v.clone()), [1, 2, 3]);
+        assert_eq!(v.replace(vec![]), [1, 2, 3]);
+
+        let w: Global<u8> = Global::lazy(|| unreachable!());
+        w.set(5);
+        assert_eq!(w.get(), 5);
+    }
+
+    #[test]
+    fn global_lazy_and_drop() {
+        let rc = Rc::new(());
+        let g: Global<Rc<()>> = Global::lazy(|| unreachable!());
+        drop(g); // never initialized, so nothing is dropped
+        let g = Global::new(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        g.set(Rc::new(()));
+        assert_eq!(Rc::strong_count(&rc), 1);
+        drop(g);
+    }
+
+    #[test]
+    fn global_other_thread() {
+        static SHARED: Global<u32> = Global::new(0);
+        SHARED.set(1);
+        let other = std::thread::spawn(|| SHARED.get()).join();
+        assert!(other.is_err());
+        assert_eq!(SHARED.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn global_reentrant() {
+        let g = Global::new(0);
+        g.with(|_| g.get());
+    }
+}
@@ -0,0 +1,349 @@
+use super::ReaderTrait;
+use alloc::string::String;
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadErrorKind {
+    /// The input ended where a token or a character was expected.
+    Eof,
+    /// The number does not fit in the requested type.
+    Overflow,
+    /// The token contains a byte that is not allowed (e.g., a non-digit in an integer, or invalid UTF-8 in a word).
+    InvalidDigit,
+    /// The number is outside of the range given to a `try_*_in` method.
+    OutOfRange,
+    /// (Strict mode) A number has a leading zero or is written as `-0`.
+    LeadingZero,
+    /// (Strict mode) The byte differs from the expected whitespace, or input remains where EOF is expected.
+    Unexpected,
+}
+
+/// Error returned by the methods of [`CheckedReader`].
+///
+/// `offset` is the number of bytes consumed by the `CheckedReader` before the offending byte
+/// (for `Overflow`, `OutOfRange` and `LeadingZero`, the start of the offending token).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadError {
+    pub offset: usize,
+    pub kind: ReadErrorKind,
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self.kind {
+            ReadErrorKind::Eof => "unexpected EOF",
+            ReadErrorKind::Overflow => "integer overflow",
+            ReadErrorKind::InvalidDigit => "invalid digit",
+            ReadErrorKind::OutOfRange => "value out of range",
+            ReadErrorKind::LeadingZero => "leading zero",
+            ReadErrorKind::Unexpected => "unexpected character",
+        };
+        write!(f, "{} at byte {}", msg, self.offset)
+    }
+}
+
+/// Reader wrapper that validates the input and reports errors instead of producing garbage.
+///
+/// In the default (lenient) mode, whitespace before each token is skipped as in `ReaderTrait`.
+/// In strict mode (see [`CheckedReader::strict()`]), which is meant for writing input validators
+/// in the spirit of testlib, no whitespace is skipped implicitly: the separators have to be consumed
+/// exactly by [`CheckedReader::try_space()`] and [`CheckedReader::try_eoln()`], the end of input has to be
+/// checked by [`CheckedReader::try_eof()`], and numbers with leading zeros or `-0` are rejected.
+///
+/// Since the input is processed a byte at a time, this is slower than the unchecked methods of `ReaderTrait`.
+/// ```no_run
+/// use basm_std::platform::io::{CheckedReader, Reader};
+/// let mut inf = CheckedReader::strict(Reader::<128>::new());
+/// let n = inf.try_i64_in(1, 100_000).unwrap();
+/// inf.try_eoln().unwrap();
+/// for i in 0..n {
+///     inf.try_i32_in(-1_000_000_000, 1_000_000_000).unwrap();
+///     if i + 1 < n { inf.try_space().unwrap(); }
+/// }
+/// inf.try_eoln().unwrap();
+/// inf.try_eof().unwrap();
+/// ```
+pub struct CheckedReader<R: ReaderTrait> {
+    inner: R,
+    offset: usize,
+    strict: bool,
+}
+
+macro_rules! impl_try_int {
+    ($($ty:ident $name:ident $name_in:ident),*) => {
+        $(
+            /// Reads an integer token, failing if it is malformed or does not fit in the type.
+            pub fn $name(&mut self) -> Result<$ty, ReadError> {
+                let (start, neg, mag) = self.int_token($ty::MIN != 0)?;
+                let out = if neg {
+                    0i128.checked_sub_unsigned(mag).and_then(|x| $ty::try_from(x).ok())
+                } else {
+                    $ty::try_from(mag).ok()
+                };
+                out.ok_or(ReadError { offset: start, kind: ReadErrorKind::Overflow })
+            }
+            /// Reads an integer token in the closed range `[l, r]`.
+            pub fn $name_in(&mut self, l: $ty, r: $ty) -> Result<$ty, ReadError> {
+                self.skip_whitespace();
+                let start = self.offset;
+                let x = self.$name()?;
+                if x < l || x > r {
+                    return Err(ReadError { offset: start, kind: ReadErrorKind::OutOfRange });
+                }
+                Ok(x)
+            }
+        )*
+    }
+}
+
+impl<R: ReaderTrait> CheckedReader<R> {
+    /// Wraps `inner` in lenient mode.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            strict: false,
+        }
+    }
+    /// Wraps `inner` in strict mode.
+    pub fn strict(inner: R) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            strict: true,
+        }
+    }
+    /// Returns the number of bytes consumed so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        if self.inner.remain().is_empty() {
+            self.inner.try_refill(1);
+        }
+        self.inner.remain().first().copied()
+    }
+    fn bump(&mut self) {
+        self.inner.try_consume(1);
+        self.offset += 1;
+    }
+    fn err<T>(&self, kind: ReadErrorKind) -> Result<T, ReadError> {
+        Err(ReadError {
+            offset: self.offset,
+            kind,
+        })
+    }
+    fn skip_whitespace(&mut self) {
+        if !self.strict {
+            while self.peek().is_some_and(|c| c <= b' ') {
+                self.bump();
+            }
+        }
+    }
+    /// Returns the offset of the token start, the sign and the magnitude.
+    fn int_token(&mut self, signed: bool) -> Result<(usize, bool, u128), ReadError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        let neg = signed && self.peek() == Some(b'-');
+        if neg {
+            self.bump();
+        }
+        let digits_start = self.offset;
+        let leading_zero = self.peek() == Some(b'0');
+        let mut mag = 0u128;
+        let mut overflow = false;
+        loop {
+            match self.peek() {
+                Some(c @ b'0'..=b'9') => {
+                    match mag
+                        .checked_mul(10)
+                        .and_then(|x| x.checked_add((c - b'0') as u128))
+                    {
+                        Some(x) => mag = x,
+                        None => overflow = true,
+                    }
+                    self.bump();
+                }
+                Some(c) if c > b' ' => return self.err(ReadErrorKind::InvalidDigit),
+                None if self.offset == digits_start => return self.err(ReadErrorKind::Eof),
+                _ if self.offset == digits_start => return self.err(ReadErrorKind::Unexpected),
+                _ => break,
+            }
+        }
+        let len = self.offset - digits_start;
+        if overflow {
+            return Err(ReadError {
+                offset: start,
+                kind: ReadErrorKind::Overflow,
+            });
+        }
+        if self.strict && ((len > 1 && leading_zero) || (neg && mag == 0)) {
+            return Err(ReadError {
+                offset: start,
+                kind: ReadErrorKind::LeadingZero,
+            });
+        }
+        Ok((start, neg, mag))
+    }
+
+    impl_try_int!(
+        i8 try_i8 try_i8_in,
+        i16 try_i16 try_i16_in,
+        i32 try_i32 try_i32_in,
+        i64 try_i64 try_i64_in,
+        i128 try_i128 try_i128_in,
+        isize try_isize try_isize_in,
+        u8 try_u8 try_u8_in,
+        u16 try_u16 try_u16_in,
+        u32 try_u32 try_u32_in,
+        u64 try_u64 try_u64_in,
+        u128 try_u128 try_u128_in,
+        usize try_usize try_usize_in
+    );
+
+    /// Reads a token of non-whitespace bytes, failing at EOF or if the token is not valid UTF-8.
+    pub fn try_word(&mut self) -> Result<String, ReadError> {
+        self.skip_whitespace();
+        let start = self.offset;
+        let mut out = alloc::vec::Vec::new();
+        while let Some(c) = self.peek().filter(|&c| c > b' ') {
+            out.push(c);
+            self.bump();
+        }
+        if out.is_empty() {
+            let kind = match self.peek() {
+                None => ReadErrorKind::Eof,
+                Some(_) => ReadErrorKind::Unexpected,
+            };
+            return self.err(kind);
+        }
+        String::from_utf8(out).or(Err(ReadError {
+            offset: start,
+            kind: ReadErrorKind::InvalidDigit,
+        }))
+    }
+    fn expect(&mut self, c: u8) -> Result<(), ReadError> {
+        match self.peek() {
+            Some(x) if x == c => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) => self.err(ReadErrorKind::Unexpected),
+            None => self.err(ReadErrorKind::Eof),
+        }
+    }
+    /// Consumes a single space (`' '`).
+    pub fn try_space(&mut self) -> Result<(), ReadError> {
+        self.expect(b' ')
+    }
+    /// Consumes an end of line (`"\n"` or `"\r\n"`).
+    pub fn try_eoln(&mut self) -> Result<(), ReadError> {
+        if self.peek() == Some(b'\r') {
+            self.bump();
+        }
+        self.expect(b'\n')
+    }
+    /// Checks that the whole input has been consumed.
+    /// In lenient mode, trailing whitespace is skipped first.
+    pub fn try_eof(&mut self) -> Result<(), ReadError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(_) => self.err(ReadErrorKind::Unexpected),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::platform::io::SliceReader;
+
+    fn lenient(data: &[u8]) -> CheckedReader<SliceReader<'_>> {
+        CheckedReader::new(SliceReader::new(data))
+    }
+
+    fn strict(data: &[u8]) -> CheckedReader<SliceReader<'_>> {
+        CheckedReader::strict(SliceReader::new(data))
+    }
+
+    fn error(offset: usize, kind: ReadErrorKind) -> ReadError {
+        ReadError { offset, kind }
+    }
+
+    #[test]
+    fn lenient_errors() {
+        let mut r = lenient(b"  12 -34\n\t18446744073709551616 12a -");
+        assert_eq!(Ok(12), r.try_i64());
+        assert_eq!(Err(error(5, ReadErrorKind::InvalidDigit)), r.try_u32());
+        assert_eq!(Ok(-34), r.try_i32());
+        assert_eq!(Ok(u64::MAX), lenient(b"18446744073709551615").try_u64());
+        assert_eq!(Err(error(10, ReadErrorKind::Overflow)), r.try_u64());
+        assert_eq!(Err(error(33, ReadErrorKind::InvalidDigit)), r.try_i64());
+        assert_eq!(Ok(String::from("a")), r.try_word());
+        assert_eq!(Err(error(36, ReadErrorKind::Eof)), r.try_i64());
+        assert_eq!(Ok(()), r.try_eof());
+        assert_eq!(
+            Err(error(2, ReadErrorKind::Eof)),
+            lenient(b" \n").try_word()
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let mut r = lenient(b"-128 128 -9223372036854775808 -9223372036854775809 -0 007");
+        assert_eq!(Ok(-128), r.try_i8());
+        assert_eq!(Err(error(5, ReadErrorKind::Overflow)), r.try_i8());
+        assert_eq!(Ok(i64::MIN), r.try_i64());
+        assert_eq!(Err(error(30, ReadErrorKind::Overflow)), r.try_i64());
+        assert_eq!(Ok(0), r.try_i64());
+        assert_eq!(
+            Err(error(54, ReadErrorKind::OutOfRange)),
+            r.try_u8_in(8, 10)
+        );
+        assert_eq!(
+            Err(error(0, ReadErrorKind::InvalidDigit)),
+            lenient(b"-1").try_u64()
+        );
+    }
+
+    #[test]
+    fn strict_validator() {
+        let mut r = strict(b"3\n1 -2 3\n");
+        let n = r.try_usize_in(1, 10).unwrap();
+        r.try_eoln().unwrap();
+        for i in 0..n {
+            r.try_i32_in(-5, 5).unwrap();
+            if i + 1 < n {
+                r.try_space().unwrap();
+            }
+        }
+        r.try_eoln().unwrap();
+        assert_eq!(Ok(()), r.try_eof());
+
+        let mut r = strict(b"1  2\n");
+        r.try_i32().unwrap();
+        r.try_space().unwrap();
+        assert_eq!(Err(error(2, ReadErrorKind::Unexpected)), r.try_i32());
+        assert_eq!(Err(error(2, ReadErrorKind::Unexpected)), r.try_eoln());
+
+        assert_eq!(
+            Err(error(0, ReadErrorKind::LeadingZero)),
+            strict(b"01").try_i32()
+        );
+        assert_eq!(
+            Err(error(0, ReadErrorKind::LeadingZero)),
+            strict(b"-0").try_i32()
+        );
+        assert_eq!(Ok(0), strict(b"0").try_i32());
+        let mut r = strict(b"5\r\n\n");
+        r.try_i32().unwrap();
+        r.try_eoln().unwrap();
+        assert_eq!(Err(error(3, ReadErrorKind::Unexpected)), r.try_eof());
+        assert_eq!(Err(error(0, ReadErrorKind::Eof)), strict(b"").try_eoln());
+    }
+}
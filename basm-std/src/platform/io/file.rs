@@ -0,0 +1,119 @@
+use super::reader::ReaderBufferTrait;
+use super::{Reader, Writer};
+use crate::platform::services;
+use core::ops::{Deref, DerefMut};
+
+/// Buffered reader from a file, e.g., `input.txt` for judges that do not use standard input.
+///
+/// This is only available on Linux targets; it panics if the binary is run on another operating system.
+/// ```no_run
+/// use basm_std::platform::io::{FileReader, ReaderTrait};
+/// let mut reader = FileReader::<128>::open("input.txt");
+/// let n = reader.usize();
+/// ```
+pub struct FileReader<const N: usize = { super::DEFAULT_BUF_SIZE }> {
+    reader: Reader<N>,
+    fd: usize,
+}
+
+impl<const N: usize> FileReader<N> {
+    /// Opens the file at `path` for reading. Panics if the file cannot be opened.
+    pub fn open(path: &str) -> Self {
+        Self {
+            reader: Reader::new(),
+            fd: services::open_file(path, false),
+        }
+    }
+}
+
+impl<const N: usize> Drop for FileReader<N> {
+    fn drop(&mut self) {
+        services::close_file(self.fd);
+    }
+}
+
+impl<const N: usize> ReaderBufferTrait for FileReader<N> {
+    fn try_refill_internal(&mut self, readahead: usize) -> usize {
+        self.reader.refill_from(self.fd, readahead)
+    }
+    fn remain_internal(&self) -> &[u8] {
+        self.reader.remain_internal()
+    }
+    fn advance(&mut self, bytes: usize) {
+        self.reader.advance(bytes)
+    }
+}
+
+/// Buffered writer to a file, e.g., `output.txt` for judges that do not use standard output.
+/// The file is created if it does not exist, and truncated otherwise.
+///
+/// `FileWriter` dereferences to [`Writer`], so all of its methods (including `Print`) are available.
+/// The buffer is flushed and the file is closed when dropped.
+/// This is only available on Linux targets; it panics if the binary is run on another operating system.
+/// ```no_run
+/// use basm_std::platform::io::{FileWriter, Print};
+/// let mut writer = FileWriter::<128>::create("output.txt");
+/// writer.println(42);
+/// ```
+pub struct FileWriter<const N: usize = { super::DEFAULT_BUF_SIZE }> {
+    writer: Writer<N>,
+}
+
+impl<const N: usize> FileWriter<N> {
+    /// Creates (or truncates) the file at `path` for writing. Panics if the file cannot be opened.
+    pub fn create(path: &str) -> Self {
+        Self {
+            writer: Writer::with_fd(services::open_file(path, true)),
+        }
+    }
+}
+
+impl<const N: usize> Drop for FileWriter<N> {
+    fn drop(&mut self) {
+        self.writer.flush();
+        services::close_file(self.writer.fd());
+    }
+}
+
+impl<const N: usize> Deref for FileWriter<N> {
+    type Target = Writer<N>;
+    fn deref(&self) -> &Writer<N> {
+        &self.writer
+    }
+}
+
+impl<const N: usize> DerefMut for FileWriter<N> {
+    fn deref_mut(&mut self) -> &mut Writer<N> {
+        &mut self.writer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::platform::io::{Print, ReaderTrait};
+
+    #[test]
+    fn write_then_read_file() {
+        let path = std::env::temp_dir().join(format!("basm-file-io-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        {
+            let mut writer = FileWriter::<128>::create(path);
+            writer.println(3);
+            for i in 0..300 {
+                writer.print(i);
+                writer.print(' ');
+            }
+            writer.println("end");
+        }
+        let mut reader = FileReader::<128>::open(path);
+        let n = reader.usize();
+        assert_eq!(n, 3);
+        let s: u32 = (0..300).map(|_| reader.u32()).sum();
+        assert_eq!(s, 299 * 300 / 2);
+        assert_eq!(reader.word(), "end");
+        assert!(reader.is_eof_skip_whitespace());
+        drop(reader);
+        std::fs::remove_file(path).unwrap();
+    }
+}
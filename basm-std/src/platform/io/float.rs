@@ -0,0 +1,399 @@
+//! Exact decimal formatting of `f64` with a fixed number of digits.
+//!
+//! A finite `f64` is exactly `m * 2^e`, which is in turn exactly `D * 10^k` for some integer `D`
+//! (when `e < 0`, `D = m * 5^(-e)` and `k = e`). We compute all decimal digits of `D` with a small
+//! stack-allocated bignum and then round the digit string itself (half to even), so the output
+//! is correctly rounded regardless of the magnitude or the number of requested digits.
+
+// m * 5^1074 < 2^2548 fits in 80 limbs; m * 2^971 < 2^1024 fits in 32 limbs.
+const LIMBS: usize = 82;
+// m * 5^1074 < 10^768
+const MAX_DIGITS: usize = 770;
+
+/// Exact decimal representation of a finite nonzero `f64`: `digits * 10^exp`.
+/// `digits[0]` is a spare slot (always 0 before rounding) that absorbs a carry from rounding.
+pub(super) struct Decimal {
+    buf: [u8; MAX_DIGITS + 1],
+    len: usize,
+    exp: i32,
+}
+
+impl Decimal {
+    #[cfg(test)]
+    fn digits(&self) -> &[u8] {
+        &self.buf[1..=self.len]
+    }
+
+    /// Rounds (half to even) so that only the first `keep` digits remain significant.
+    /// Returns the number of digits of the rounded value in `buf[..=keep]`, which may contain a leading 0.
+    fn round(&mut self, keep: usize) -> usize {
+        if keep >= self.len {
+            return keep;
+        }
+        let d = self.buf[keep + 1];
+        let up = if d != 5 {
+            d > 5
+        } else {
+            self.buf[keep + 2..=self.len].iter().any(|&x| x != 0) || self.buf[keep] % 2 == 1
+        };
+        if up {
+            let mut i = keep;
+            loop {
+                if self.buf[i] == 9 {
+                    self.buf[i] = 0;
+                    i -= 1;
+                } else {
+                    self.buf[i] += 1;
+                    break;
+                }
+            }
+        }
+        keep
+    }
+}
+
+fn mul_small(big: &mut [u32; LIMBS], len: &mut usize, x: u32) {
+    let mut carry = 0u64;
+    for limb in big[..*len].iter_mut() {
+        let v = *limb as u64 * x as u64 + carry;
+        *limb = v as u32;
+        carry = v >> 32;
+    }
+    if carry > 0 {
+        big[*len] = carry as u32;
+        *len += 1;
+    }
+}
+
+fn divrem_small(big: &mut [u32; LIMBS], len: &mut usize, x: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in big[..*len].iter_mut().rev() {
+        let v = (rem << 32) | *limb as u64;
+        *limb = (v / x as u64) as u32;
+        rem = v % x as u64;
+    }
+    while *len > 0 && big[*len - 1] == 0 {
+        *len -= 1;
+    }
+    rem as u32
+}
+
+/// Decomposes a finite nonzero `f` into its exact decimal digits.
+pub(super) fn decompose(f: f64) -> Decimal {
+    let bits = f.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i32;
+    let frac = bits & ((1u64 << 52) - 1);
+    let (mut m, mut e) = if biased == 0 {
+        (frac, -1074)
+    } else {
+        (frac | (1u64 << 52), biased - 1075)
+    };
+    debug_assert!(m != 0);
+    let tz = m.trailing_zeros();
+    m >>= tz;
+    e += tz as i32;
+
+    let mut big = [0u32; LIMBS];
+    big[0] = m as u32;
+    big[1] = (m >> 32) as u32;
+    let mut len = if big[1] != 0 { 2 } else { 1 };
+    let exp = if e >= 0 {
+        let (limbs, bits) = (e as usize / 32, e as u32 % 32);
+        if bits > 0 {
+            mul_small(&mut big, &mut len, 1 << bits);
+        }
+        big.copy_within(0..len, limbs);
+        big[..limbs].fill(0);
+        len += limbs;
+        0
+    } else {
+        let mut k = -e;
+        while k >= 13 {
+            mul_small(&mut big, &mut len, 1_220_703_125); // 5^13
+            k -= 13;
+        }
+        mul_small(&mut big, &mut len, 5u32.pow(k as u32));
+        e
+    };
+
+    // Extract digits from the least significant end, nine at a time.
+    let mut out = Decimal {
+        buf: [0; MAX_DIGITS + 1],
+        len: 0,
+        exp,
+    };
+    let mut rev = [0u8; MAX_DIGITS + 9];
+    let mut n = 0;
+    while len > 0 {
+        let mut r = divrem_small(&mut big, &mut len, 1_000_000_000);
+        for _ in 0..9 {
+            rev[n] = (r % 10) as u8;
+            r /= 10;
+            n += 1;
+        }
+    }
+    while rev[n - 1] == 0 {
+        n -= 1;
+    }
+    let mut lo = 0;
+    while rev[lo] == 0 {
+        lo += 1;
+    }
+    out.exp += lo as i32;
+    out.len = n - lo;
+    for (i, &d) in rev[lo..n].iter().rev().enumerate() {
+        out.buf[i + 1] = d;
+    }
+    out
+}
+
+/// Emits `iter` to `sink` in chunks, without allocating.
+fn emit(sink: &mut impl FnMut(&[u8]), iter: impl Iterator<Item = u8>) {
+    let mut chunk = [0u8; 64];
+    let mut n = 0;
+    for b in iter {
+        chunk[n] = b;
+        n += 1;
+        if n == chunk.len() {
+            sink(&chunk);
+            n = 0;
+        }
+    }
+    sink(&chunk[..n]);
+}
+
+fn special(f: f64, sink: &mut impl FnMut(&[u8])) -> bool {
+    if f.is_nan() {
+        sink(b"NaN");
+    } else if f.is_infinite() {
+        sink(if f < 0.0 { b"-inf" } else { b"inf" });
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Writes `f` with exactly `prec` digits after the decimal point (like `printf("%.*f")`),
+/// except that a result that rounds to zero is written without a minus sign.
+pub(super) fn write_fixed(f: f64, prec: usize, sink: &mut impl FnMut(&[u8])) {
+    if special(f, sink) {
+        return;
+    }
+    // `digits` holds the significant digits of round(|f| * 10^prec), followed by `zeros` zeros.
+    let mut dec;
+    let (digits, zeros): (&[u8], usize) = if f == 0.0 {
+        (&[], 0)
+    } else {
+        dec = decompose(f);
+        // Number of digits whose decimal exponent is at least -prec.
+        let keep = dec.len as i64 + dec.exp as i64 + prec as i64;
+        if keep < 0 {
+            (&[], 0)
+        } else {
+            let len = dec.len;
+            let n = dec.round(keep as usize);
+            let (buf, zeros) = if n > len {
+                (&dec.buf[..=len], n - len)
+            } else {
+                (&dec.buf[..=n], 0)
+            };
+            let lead = buf.iter().position(|&x| x != 0).unwrap_or(buf.len());
+            (&buf[lead..], if lead == buf.len() { 0 } else { zeros })
+        }
+    };
+    let total = digits.len() + zeros;
+    if f.is_sign_negative() && total > 0 {
+        sink(b"-");
+    }
+    let width = total.max(prec + 1);
+    let pad = width - total;
+    let int_len = width - prec;
+    let all = (0..pad)
+        .map(|_| b'0')
+        .chain(digits.iter().map(|&d| b'0' + d))
+        .chain((0..zeros).map(|_| b'0'));
+    emit(
+        sink,
+        all.enumerate().flat_map(|(i, c)| {
+            let dot = (i == int_len && prec > 0).then_some(b'.');
+            dot.into_iter().chain(core::iter::once(c))
+        }),
+    );
+}
+
+/// Writes `f` in scientific notation with exactly `prec` digits after the decimal point
+/// and an exponent of at least two digits (like `printf("%.*e")`).
+pub(super) fn write_sci(f: f64, prec: usize, sink: &mut impl FnMut(&[u8])) {
+    if special(f, sink) {
+        return;
+    }
+    let mut dec;
+    let (digits, exp): (&[u8], i32) = if f == 0.0 {
+        (&[], 0)
+    } else {
+        dec = decompose(f);
+        let len = dec.len;
+        let mut exp = dec.exp + len as i32 - 1;
+        let n = dec.round(prec + 1).min(len);
+        if dec.buf[0] != 0 {
+            // Carried into a new leading digit (e.g., 9.99 -> 10.0)
+            exp += 1;
+            (&dec.buf[..n], exp)
+        } else {
+            (&dec.buf[1..=n], exp)
+        }
+    };
+    // Unlike `write_fixed`, the sign of zero is kept, as in `printf("%e")`
+    if f.is_sign_negative() {
+        sink(b"-");
+    }
+    let mantissa = (0..prec + 1).map(|i| b'0' + digits.get(i).copied().unwrap_or(0));
+    emit(
+        sink,
+        mantissa.enumerate().flat_map(|(i, c)| {
+            let dot = (i == 1).then_some(b'.');
+            dot.into_iter().chain(core::iter::once(c))
+        }),
+    );
+    let mut e = [b'e', if exp < 0 { b'-' } else { b'+' }, 0, 0, 0];
+    let a = exp.unsigned_abs();
+    let n = if a >= 100 {
+        e[2] = b'0' + (a / 100) as u8;
+        e[3] = b'0' + (a / 10 % 10) as u8;
+        e[4] = b'0' + (a % 10) as u8;
+        5
+    } else {
+        e[2] = b'0' + (a / 10) as u8;
+        e[3] = b'0' + (a % 10) as u8;
+        4
+    };
+    sink(&e[..n]);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    fn fixed(f: f64, prec: usize) -> String {
+        let mut out = Vec::new();
+        write_fixed(f, prec, &mut |s: &[u8]| out.extend_from_slice(s));
+        String::from_utf8(out).unwrap()
+    }
+
+    fn sci(f: f64, prec: usize) -> String {
+        let mut out = Vec::new();
+        write_sci(f, prec, &mut |s: &[u8]| out.extend_from_slice(s));
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn decompose_exact() {
+        let d = decompose(0.125);
+        assert_eq!(d.digits(), [1, 2, 5]);
+        assert_eq!(d.exp, -3);
+        let d = decompose(1e22);
+        assert_eq!(d.digits(), [1]);
+        assert_eq!(d.exp, 22);
+        let d = decompose(f64::from_bits(1));
+        assert_eq!(d.len, 751);
+        assert_eq!(d.exp, -1074);
+    }
+
+    #[test]
+    fn fixed_matches_std() {
+        let values = [
+            0.0,
+            1.0,
+            -1.5,
+            0.125,
+            0.375,
+            2.5,
+            3.5,
+            0.1,
+            0.3,
+            1.0 / 3.0,
+            -2.0 / 3.0,
+            123456.789,
+            9.9999999,
+            0.0009995,
+            1e-7,
+            -1e-300,
+            6.02214076e23,
+            1e300,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::from_bits(1),
+        ];
+        for &f in &values {
+            for prec in [0, 1, 2, 3, 6, 9, 17, 30] {
+                let mut expected = alloc::format!("{:.*}", prec, f);
+                if expected
+                    .bytes()
+                    .all(|c| c == b'-' || c == b'0' || c == b'.')
+                {
+                    expected = expected.trim_start_matches('-').into();
+                }
+                assert_eq!(expected, fixed(f, prec), "{f:e} with {prec} digits");
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_negative_zero() {
+        assert_eq!("0.000", fixed(-0.0, 3));
+        assert_eq!("0.000", fixed(-0.0001, 3));
+        assert_eq!("-0.001", fixed(-0.0005001, 3));
+        assert_eq!("0", fixed(-0.4, 0));
+    }
+
+    #[test]
+    fn fixed_special() {
+        assert_eq!("NaN", fixed(f64::NAN, 3));
+        assert_eq!("inf", fixed(f64::INFINITY, 3));
+        assert_eq!("-inf", fixed(f64::NEG_INFINITY, 3));
+    }
+
+    #[test]
+    fn sci_matches_printf() {
+        assert_eq!("1.234560e+05", sci(123456.0, 6));
+        assert_eq!("1.000e+01", sci(9.9996, 3));
+        assert_eq!("1.2e-07", sci(1.25e-7, 1));
+        assert_eq!("-3e+00", sci(-3.0, 0));
+        assert_eq!("0.000000e+00", sci(0.0, 6));
+        assert_eq!("-0.00e+00", sci(-0.0, 2));
+        assert_eq!("1.797693134862316e+308", sci(f64::MAX, 15));
+        assert_eq!("4.9406564584124654e-324", sci(f64::from_bits(1), 16));
+        assert_eq!("1.00000000000000000000e+22", sci(1e22, 20));
+    }
+
+    #[test]
+    fn sci_matches_std_mantissa() {
+        let values = [
+            0.1,
+            1.0 / 3.0,
+            123456.789,
+            6.02214076e23,
+            2.5e-300,
+            7.0,
+            0.0,
+            -0.0,
+            -2.5,
+        ];
+        for &f in &values {
+            for prec in [0, 1, 5, 10, 20] {
+                let std = alloc::format!("{:.*e}", prec, f);
+                let (mantissa, exp) = std.split_once('e').unwrap();
+                let exp: i32 = exp.parse().unwrap();
+                let expected = alloc::format!(
+                    "{}e{}{:02}",
+                    mantissa,
+                    if exp < 0 { '-' } else { '+' },
+                    exp.unsigned_abs()
+                );
+                assert_eq!(expected, sci(f, prec));
+            }
+        }
+    }
+}
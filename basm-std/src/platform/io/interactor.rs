@@ -0,0 +1,165 @@
+use super::reader::ReaderBufferTrait;
+use super::{Print, Readable, Reader, Writer};
+
+/// A `Reader` and a `Writer` tied together for interactive problems.
+///
+/// Before the reader blocks waiting for more input, any buffered output is flushed,
+/// so that the judge always sees our queries before we wait for its answers.
+/// `Interactor` implements both `ReaderTrait` and `Print`, so it can be used in place of both.
+/// ```no_run
+/// use basm_std::platform::io::{Interactor, Print, ReaderTrait};
+/// let mut io: Interactor = Default::default();
+/// let n = io.usize();
+/// let (mut lo, mut hi) = (1, n);
+/// while lo < hi {
+///     let mid = (lo + hi) / 2;
+///     let ans: String = io.query(("?", mid));
+///     if ans == "<" { hi = mid; } else { lo = mid + 1; }
+/// }
+/// io.println(("!", lo));
+/// ```
+pub struct Interactor<
+    const R: usize = { super::DEFAULT_BUF_SIZE },
+    const W: usize = { super::DEFAULT_BUF_SIZE },
+> {
+    pub reader: Reader<R>,
+    pub writer: Writer<W>,
+}
+
+impl<const R: usize, const W: usize> Default for Interactor<R, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const R: usize, const W: usize> Interactor<R, W> {
+    pub fn new() -> Self {
+        Self {
+            reader: Reader::new(),
+            writer: Writer::new(),
+        }
+    }
+    /// Flushes the buffered output.
+    pub fn flush(&mut self) {
+        self.writer.flush();
+    }
+    /// Writes `q` followed by a newline, flushes, and reads the answer of type `A`.
+    pub fn query<Q, A: Readable>(&mut self, q: Q) -> A
+    where
+        Writer<W>: Print<Q>,
+    {
+        self.writer.println(q);
+        self.writer.flush();
+        A::read(self)
+    }
+}
+
+impl<const R: usize, const W: usize> ReaderBufferTrait for Interactor<R, W> {
+    fn try_refill_internal(&mut self, readahead: usize) -> usize {
+        if self.reader.remain_internal().len() < readahead && !self.writer.is_empty() {
+            self.writer.flush();
+        }
+        self.reader.try_refill_internal(readahead)
+    }
+    fn remain_internal(&self) -> &[u8] {
+        self.reader.remain_internal()
+    }
+    fn advance(&mut self, bytes: usize) {
+        self.reader.advance(bytes)
+    }
+}
+
+impl<T, const R: usize, const W: usize> Print<T> for Interactor<R, W>
+where
+    Writer<W>: Print<T>,
+{
+    fn print(&mut self, x: T) {
+        self.writer.print(x);
+    }
+    fn println(&mut self, x: T) {
+        self.writer.println(x);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::platform::io::ReaderTrait;
+    use crate::platform::testing::run_interactive;
+    use alloc::string::String;
+    use std::io::{BufRead, Write};
+
+    fn read_line(reader: &mut impl BufRead) -> String {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        line.trim_end().into()
+    }
+
+    #[test]
+    fn guess_number_with_query() {
+        const SECRET: u32 = 37;
+        let queries = run_interactive(
+            || {
+                let mut io = Interactor::<128, 128>::new();
+                let n = io.u32();
+                let (mut lo, mut hi) = (1, n);
+                loop {
+                    let mid = (lo + hi) / 2;
+                    let ans: String = io.query(("?", mid));
+                    match ans.as_str() {
+                        "<" => hi = mid - 1,
+                        ">" => lo = mid + 1,
+                        _ => break,
+                    }
+                }
+                io.println("!");
+            },
+            |input, output| {
+                writeln!(output, "100").unwrap();
+                let mut queries = 0;
+                loop {
+                    let line = read_line(input);
+                    let Some(x) = line.strip_prefix("? ") else {
+                        assert_eq!("!", line);
+                        break queries;
+                    };
+                    queries += 1;
+                    let x: u32 = x.parse().unwrap();
+                    let ans = match x.cmp(&SECRET) {
+                        core::cmp::Ordering::Less => ">",
+                        core::cmp::Ordering::Greater => "<",
+                        core::cmp::Ordering::Equal => "=",
+                    };
+                    writeln!(output, "{}", ans).unwrap();
+                }
+            },
+        );
+        assert!(queries <= 7);
+    }
+
+    #[test]
+    fn flush_before_refill() {
+        // The solution never flushes explicitly; reading must flush the pending output.
+        let total = run_interactive(
+            || {
+                let mut io = Interactor::<128, 128>::new();
+                let mut x = 1u64;
+                for _ in 0..10 {
+                    io.println(x);
+                    x = io.u64() * 2;
+                }
+                io.println(x);
+            },
+            |input, output| {
+                let mut total = 0;
+                for _ in 0..10 {
+                    let x: u64 = read_line(input).parse().unwrap();
+                    total += x;
+                    writeln!(output, "{}", x + 1).unwrap();
+                }
+                (total, read_line(input))
+            },
+        );
+        assert_eq!((3049, String::from("3070")), total);
+    }
+}
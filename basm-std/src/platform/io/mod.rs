@@ -1,9 +1,20 @@
-#[cfg(all(target_arch = "x86_64", not(test)))]
+#[cfg(not(test))]
 pub use reader::MmapReader;
 mod reader;
-pub use reader::{Readable, Reader, ReaderTrait};
+pub use reader::{Readable, Reader, ReaderTrait, SliceReader, VecReader};
+mod checked;
+pub use checked::{CheckedReader, ReadError, ReadErrorKind};
+#[cfg(target_os = "linux")]
+mod file;
+#[cfg(target_os = "linux")]
+pub use file::{FileReader, FileWriter};
+mod float;
+mod interactor;
+pub use interactor::Interactor;
+mod testcases;
+pub use testcases::{CaseFormat, TestCases};
 mod writer;
-pub use writer::{Print, Writer};
+pub use writer::{ErrWriter, Fixed, Print, Sci, Writer};
 mod reader_traits;
 pub use reader_traits::*;
 const DEFAULT_BUF_SIZE: usize = 1 << 16;
@@ -1,5 +1,6 @@
 use crate::platform::services;
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::mem::MaybeUninit;
 use core::str::FromStr;
 
@@ -29,10 +30,217 @@ mod position {
     pub unsafe fn memchr(s: &[u8], delim: u8) -> Option<usize> {
         s.iter().position(|&b| b == delim)
     }
+    /// Sets bit `start + i` of `out` (LSB-first) to the lowest bit of `s[i]` (`b'0'` or `b'1'`).
+    /// The bits to be set must be cleared beforehand.
+    #[cfg_attr(
+        any(target_arch = "x86_64", target_arch = "x86"),
+        target_feature(enable = "avx2")
+    )]
+    pub unsafe fn pack_bits(s: &[u8], out: &mut [u64], start: usize) {
+        let head = ((64 - start % 64) % 64).min(s.len());
+        for (i, &c) in s[..head].iter().enumerate() {
+            out[(start + i) / 64] |= ((c & 1) as u64) << ((start + i) % 64);
+        }
+        let mut k = (start + head) / 64;
+        let mut chunks = s[head..].chunks_exact(64);
+        for chunk in &mut chunks {
+            #[cfg(target_arch = "x86_64")]
+            let w = unsafe {
+                use core::arch::x86_64::*;
+                // Move the lowest bit of each byte to its sign bit, which is gathered by movemask
+                let lo = _mm256_loadu_si256(chunk.as_ptr() as *const __m256i);
+                let hi = _mm256_loadu_si256(chunk.as_ptr().add(32) as *const __m256i);
+                let lo = _mm256_movemask_epi8(_mm256_slli_epi16::<7>(lo)) as u32 as u64;
+                let hi = _mm256_movemask_epi8(_mm256_slli_epi16::<7>(hi)) as u32 as u64;
+                lo | hi << 32
+            };
+            #[cfg(not(target_arch = "x86_64"))]
+            let w = {
+                let mut w = 0u64;
+                for (j, &c) in chunk.iter().enumerate() {
+                    w |= ((c & 1) as u64) << j;
+                }
+                w
+            };
+            out[k] = w;
+            k += 1;
+        }
+        for (j, &c) in chunks.remainder().iter().enumerate() {
+            out[k] |= ((c & 1) as u64) << j;
+        }
+    }
+}
+
+#[cfg(any(not(feature = "short"), feature = "fastio"))]
+#[cfg(target_arch = "x86_64")]
+mod bulk {
+    use core::arch::x86_64::*;
+
+    /// Returns the mask of whitespace bytes (`<= b' '`) among the 64 bytes at `p`.
+    #[target_feature(enable = "avx2")]
+    unsafe fn white64(p: *const u8) -> u64 {
+        unsafe {
+            let sp = _mm256_set1_epi8(b' ' as i8);
+            let lo = _mm256_loadu_si256(p as *const __m256i);
+            let hi = _mm256_loadu_si256(p.add(32) as *const __m256i);
+            let lo = _mm256_movemask_epi8(_mm256_cmpeq_epi8(_mm256_max_epu8(lo, sp), sp));
+            let hi = _mm256_movemask_epi8(_mm256_cmpeq_epi8(_mm256_max_epu8(hi, sp), sp));
+            lo as u32 as u64 | (hi as u32 as u64) << 32
+        }
+    }
+
+    /// Parses the runs of `len_a` digits at `a` and `len_b` digits at `b` (both at most 15),
+    /// one in each 128-bit lane. Reads 16 bytes from both `a` and `b`.
+    #[target_feature(enable = "avx2")]
+    unsafe fn parse_pair(a: *const u8, len_a: usize, b: *const u8, len_b: usize) -> (u64, u64) {
+        unsafe {
+            let c = _mm256_loadu2_m128i(b as *const __m128i, a as *const __m128i);
+            // Right-align the digits, filling the upper digits with zeros
+            let d = _mm256_subs_epu8(c, _mm256_set1_epi8(b'0' as i8));
+            let idx = _mm256_add_epi8(
+                _mm256_setr_epi8(
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 0, 1, 2, 3, 4, 5, 6, 7,
+                    8, 9, 10, 11, 12, 13, 14, 15,
+                ),
+                _mm256_set_m128i(
+                    _mm_set1_epi8(len_b as i8 - 16),
+                    _mm_set1_epi8(len_a as i8 - 16),
+                ),
+            );
+            let d = _mm256_shuffle_epi8(d, idx);
+            let t1 = _mm256_maddubs_epi16(d, _mm256_set1_epi16(0x010a));
+            let t2 = _mm256_madd_epi16(t1, _mm256_set1_epi32(0x0001_0064));
+            let t3 = _mm256_packus_epi32(t2, t2);
+            let t4 = _mm256_madd_epi16(t3, _mm256_set1_epi32(0x0001_2710));
+            // The upper and lower 8 digits are in the first two dwords of each lane
+            let v = _mm256_add_epi64(
+                _mm256_mul_epu32(t4, _mm256_set1_epi64x(100_000_000)),
+                _mm256_srli_epi64::<32>(t4),
+            );
+            (
+                _mm256_extract_epi64::<0>(v) as u64,
+                _mm256_extract_epi64::<2>(v) as u64,
+            )
+        }
+    }
+
+    /// Parses the run of hexadecimal digits (case-insensitive) starting at `p`,
+    /// returning the value and the number of digits.
+    /// Reads 16 bytes from `p`; the returned length is 16 if the run does not end within them.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn hex16(p: *const u8) -> (u64, usize) {
+        unsafe {
+            let c = _mm_loadu_si128(p as *const __m128i);
+            let in_range = |x: __m128i, lo: u8, hi: u8| {
+                let x = _mm_sub_epi8(x, _mm_set1_epi8(lo as i8));
+                _mm_cmpeq_epi8(_mm_min_epu8(x, _mm_set1_epi8((hi - lo) as i8)), x)
+            };
+            let lower = _mm_or_si128(c, _mm_set1_epi8(0x20));
+            let valid = _mm_or_si128(in_range(c, b'0', b'9'), in_range(lower, b'a', b'f'));
+            let len = (!_mm_movemask_epi8(valid) as u32 | 0x10000).trailing_zeros() as usize;
+            // The low nibble is the value of a decimal digit, and 9 less than that of a letter
+            let d = _mm_add_epi8(
+                _mm_and_si128(c, _mm_set1_epi8(0x0f)),
+                _mm_and_si128(
+                    _mm_cmpgt_epi8(c, _mm_set1_epi8(b'9' as i8)),
+                    _mm_set1_epi8(9),
+                ),
+            );
+            // Right-align the digits, filling the upper digits with zeros
+            let idx = _mm_add_epi8(
+                _mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15),
+                _mm_set1_epi8(len as i8 - 16),
+            );
+            let d = _mm_shuffle_epi8(d, idx);
+            let t1 = _mm_maddubs_epi16(d, _mm_set1_epi16(0x0110));
+            let t2 = _mm_packus_epi16(t1, t1);
+            ((_mm_cvtsi128_si64(t2) as u64).swap_bytes(), len)
+        }
+    }
+
+    /// Parses up to `n` whitespace-separated integers from `data`, calling `store(i, value)` for each.
+    /// Negative values (if `signed`) are stored as wrapped `u64`s.
+    ///
+    /// The data is scanned in windows of 64 bytes. The token boundaries of a window are found from
+    /// its whitespace mask, and the tokens that end within the window are parsed two at a time.
+    /// Stops before a token that is too close to the end of `data`, crosses a whole window,
+    /// or has no digits or 16 or more digits, so that the caller can process it with the scalar routines.
+    /// Returns the number of integers parsed and the number of bytes consumed.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn parse(
+        data: &[u8],
+        n: usize,
+        signed: bool,
+        mut store: impl FnMut(usize, u64),
+    ) -> (usize, usize) {
+        let mut pos = 0;
+        let mut cnt = 0;
+        /* Returns the start of the digits and whether the token is negative */
+        let sign = |p: *const u8, start: usize| {
+            let neg = signed && unsafe { *p.add(start) } == b'-';
+            (start + neg as usize, neg)
+        };
+        let apply = |value: u64, neg: bool| if neg { value.wrapping_neg() } else { value };
+        // Each window needs 16 more bytes, since a token ending within it is loaded as 16 bytes.
+        // `pos` is always at a token boundary, so the first byte of a window cannot be inside a token.
+        while cnt < n && pos + 80 <= data.len() {
+            let p = unsafe { data.as_ptr().add(pos) };
+            let white = unsafe { white64(p) };
+            let prev_white = white << 1 | 1;
+            let mut starts = !white & prev_white;
+            let mut ends = white & !prev_white;
+            if ends == 0 {
+                if starts != 0 {
+                    break;
+                }
+                pos += 64;
+                continue;
+            }
+            let mut done = 0;
+            let mut stop = false;
+            while ends != 0 && cnt < n {
+                let (s_a, neg_a) = sign(p, starts.trailing_zeros() as usize);
+                let e_a = ends.trailing_zeros() as usize;
+                starts &= starts - 1;
+                ends &= ends - 1;
+                if !(1..16).contains(&(e_a - s_a)) {
+                    stop = true;
+                    break;
+                }
+                let (mut s_b, mut e_b, mut neg_b) = (s_a, e_a, neg_a);
+                let pair = ends != 0 && cnt + 1 < n;
+                if pair {
+                    (s_b, neg_b) = sign(p, starts.trailing_zeros() as usize);
+                    e_b = ends.trailing_zeros() as usize;
+                }
+                let pair = pair && (1..16).contains(&(e_b - s_b));
+                let (a, b) = unsafe { parse_pair(p.add(s_a), e_a - s_a, p.add(s_b), e_b - s_b) };
+                store(cnt, apply(a, neg_a));
+                cnt += 1;
+                done = e_a;
+                if pair {
+                    starts &= starts - 1;
+                    ends &= ends - 1;
+                    store(cnt, apply(b, neg_b));
+                    cnt += 1;
+                    done = e_b;
+                }
+            }
+            if stop || cnt == n || starts != 0 {
+                pos += done;
+                if stop {
+                    break;
+                }
+            } else {
+                pos += 64;
+            }
+        }
+        (cnt, pos)
+    }
 }
 
 /// Note: _internal prefix solely for avoiding name clash with public method
-trait ReaderBufferTrait: Sized {
+pub(super) trait ReaderBufferTrait: Sized {
     fn try_refill_internal(&mut self, readahead: usize) -> usize;
     fn remain_internal(&self) -> &[u8];
     fn advance(&mut self, bytes: usize); // raw functionality (cf. try_consume: has sanity checks)
@@ -155,6 +363,10 @@ pub trait ReaderTrait: Sized {
     fn word(&mut self) -> String;
     fn line(&mut self) -> String;
     fn skip_whitespace(&mut self) -> usize;
+    /// Reads `out.len()` integers into `out`. This is faster than reading them one by one.
+    fn read_i32_into(&mut self, out: &mut [i32]);
+    /// Reads `out.len()` integers into `out`. This is faster than reading them one by one.
+    fn read_u64_into(&mut self, out: &mut [u64]);
     fn next<T: Readable>(&mut self) -> T {
         T::read(self)
     }
@@ -165,6 +377,122 @@ pub trait ReaderTrait: Sized {
     fn collect<Cn: FromIterator<T>, T: Readable>(&mut self, n: usize) -> Cn {
         Cn::from_iter((0..n).map(|_| T::read(self)))
     }
+    /// Reads and collects elements of type `T` until the end of the input.
+    ///
+    /// Whitespace following the last element is ignored.
+    fn collect_until_eof<Cn: FromIterator<T>, T: Readable>(&mut self) -> Cn {
+        Cn::from_iter(core::iter::from_fn(|| {
+            (!self.is_eof_skip_whitespace()).then(|| T::read(self))
+        }))
+    }
+    /// Reads `h` rows of `w` bytes each, such as a grid of `.` and `#`.
+    ///
+    /// Each row is a token of non-whitespace bytes; if a row is shorter than `w`, the rest is filled with 0,
+    /// and if it is longer, the bytes beyond `w` are discarded.
+    fn grid(&mut self, h: usize, w: usize) -> Vec<Vec<u8>> {
+        (0..h)
+            .map(|_| {
+                let mut row = alloc::vec![0u8; w];
+                self.word_buf(&mut row);
+                self.skip_until_whitespace();
+                row
+            })
+            .collect()
+    }
+    /// Reads `h` rows of `w` bytes each into a single `Vec` where the cell `(i, j)` is at index `i * stride + j`.
+    ///
+    /// The bytes between rows (when `stride > w`) and the rest of short rows are filled with 0,
+    /// and the bytes of long rows beyond `w` are discarded.
+    fn grid_flat(&mut self, h: usize, w: usize, stride: usize) -> Vec<u8> {
+        assert!(stride >= w, "stride must be at least w");
+        let mut out = alloc::vec![0u8; h * stride];
+        for i in 0..h {
+            self.word_buf(&mut out[i * stride..i * stride + w]);
+            self.skip_until_whitespace();
+        }
+        out
+    }
+    /// Reads a string of `n` `0`/`1` characters as bits packed into `u64`s, least significant bit first.
+    /// That is, the `i`-th character is stored in bit `i % 64` of the `i / 64`-th word.
+    ///
+    /// Reading stops early at whitespace or EOF, leaving the remaining bits 0.
+    fn bits(&mut self, n: usize) -> Vec<u64> {
+        let mut out = alloc::vec![0u64; n.div_ceil(64)];
+        self.skip_whitespace();
+        let mut done = 0;
+        while done < n {
+            let data = self.remain();
+            let take = core::cmp::min(data.len(), n - done);
+            if take == 0 {
+                break;
+            }
+            let data = &data[..take];
+            let end = unsafe { position::white(data) }.unwrap_or(take);
+            unsafe { position::pack_bits(&data[..end], &mut out, done) };
+            self.try_consume(end);
+            done += end;
+            if end < take {
+                break;
+            }
+            self.try_refill(1);
+        }
+        out
+    }
+    /// Reads an unsigned integer in base `radix` (2 to 36). Letters are case-insensitive.
+    ///
+    /// Overflow wraps around, and parsing stops at the first byte that is not a digit in `radix`.
+    /// ```
+    /// use basm_std::platform::io::{ReaderTrait, SliceReader};
+    /// let mut reader = SliceReader::new(b"ff 1010 zz");
+    /// assert_eq!(reader.u64_radix(16), 255);
+    /// assert_eq!(reader.u64_radix(2), 10);
+    /// assert_eq!(reader.u64_radix(36), 1295);
+    /// ```
+    fn u64_radix(&mut self, radix: u32) -> u64 {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+        self.skip_whitespace();
+        let mut out = 0u64;
+        #[cfg(any(not(feature = "short"), feature = "fastio"))]
+        #[cfg(target_arch = "x86_64")]
+        if radix == 16 {
+            loop {
+                self.try_refill(16);
+                let data = self.remain();
+                if data.len() < 16 {
+                    break;
+                }
+                let (value, len) = unsafe { bulk::hex16(data.as_ptr()) };
+                out = if len == 16 {
+                    value
+                } else {
+                    out << (4 * len) | value
+                };
+                self.try_consume(len);
+                if len < 16 {
+                    return out;
+                }
+            }
+        }
+        loop {
+            let data = self.remain();
+            if data.is_empty() {
+                break out;
+            }
+            let mut len = 0;
+            for &c in data {
+                let Some(d) = (c as char).to_digit(radix) else {
+                    break;
+                };
+                out = out.wrapping_mul(radix as u64).wrapping_add(d as u64);
+                len += 1;
+            }
+            let partial = len < data.len();
+            self.try_consume(len);
+            if partial || self.try_refill(1) == 0 {
+                break out;
+            }
+        }
+    }
     /// Reads and collects an `n`-by-`m` matrix of type `T`.
     fn collect_2d<Cnm: FromIterator<Cm>, Cm: FromIterator<T>, T: Readable>(
         &mut self,
@@ -514,6 +842,47 @@ impl<T: ReaderBufferTrait> ReaderTrait for T {
         }
         out
     }
+    fn read_i32_into(&mut self, out: &mut [i32]) {
+        let mut i = 0;
+        while i < out.len() {
+            #[cfg(any(not(feature = "short"), feature = "fastio"))]
+            #[cfg(target_arch = "x86_64")]
+            {
+                self.try_refill(64);
+                let rest = &mut out[i..];
+                let (cnt, consumed) = unsafe {
+                    bulk::parse(self.remain(), rest.len(), true, |j, x| rest[j] = x as i32)
+                };
+                self.advance(consumed);
+                i += cnt;
+                if i == out.len() {
+                    break;
+                }
+            }
+            out[i] = self.i32();
+            i += 1;
+        }
+    }
+    fn read_u64_into(&mut self, out: &mut [u64]) {
+        let mut i = 0;
+        while i < out.len() {
+            #[cfg(any(not(feature = "short"), feature = "fastio"))]
+            #[cfg(target_arch = "x86_64")]
+            {
+                self.try_refill(64);
+                let rest = &mut out[i..];
+                let (cnt, consumed) =
+                    unsafe { bulk::parse(self.remain(), rest.len(), false, |j, x| rest[j] = x) };
+                self.advance(consumed);
+                i += cnt;
+                if i == out.len() {
+                    break;
+                }
+            }
+            out[i] = self.u64();
+            i += 1;
+        }
+    }
     // We do not use avx2 for this function since most of the time
     // we only skip a few whitespaces.
     fn skip_whitespace(&mut self) -> usize {
@@ -566,8 +935,10 @@ impl<const N: usize> Reader<N> {
     }
 }
 
-impl<const N: usize> ReaderBufferTrait for Reader<N> {
-    fn try_refill_internal(&mut self, readahead: usize) -> usize {
+impl<const N: usize> Reader<N> {
+    /// Refills the buffer from the file descriptor `fd`; see [`ReaderBufferTrait::try_refill_internal`].
+    #[inline(always)]
+    pub(super) fn refill_from(&mut self, fd: usize, readahead: usize) -> usize {
         /* readahead cannot exceed the buffer size */
         assert!(readahead <= Self::BUF_LEN);
         unsafe {
@@ -592,7 +963,7 @@ impl<const N: usize> ReaderBufferTrait for Reader<N> {
                     for i in 0..rem {
                         *self.buf[i].assume_init_mut() = self.buf[self.off + i].assume_init();
                     }
-                    rem += services::read_stdio(0, self.buf[rem..Self::BUF_LEN].assume_init_mut());
+                    rem += services::read_stdio(fd, self.buf[rem..Self::BUF_LEN].assume_init_mut());
                 }
                 #[cfg(any(not(feature = "short"), feature = "fastio"))]
                 {
@@ -619,8 +990,10 @@ impl<const N: usize> ReaderBufferTrait for Reader<N> {
                     if white_cnt == 0 {
                         /* No whitespace has been found. We have to read.
                          * We try to read as much as possible at once. */
-                        rem +=
-                            services::read_stdio(0, self.buf[rem..Self::BUF_LEN].assume_init_mut());
+                        rem += services::read_stdio(
+                            fd,
+                            self.buf[rem..Self::BUF_LEN].assume_init_mut(),
+                        );
                     }
                 }
 
@@ -637,6 +1010,12 @@ impl<const N: usize> ReaderBufferTrait for Reader<N> {
             rem
         }
     }
+}
+
+impl<const N: usize> ReaderBufferTrait for Reader<N> {
+    fn try_refill_internal(&mut self, readahead: usize) -> usize {
+        self.refill_from(0, readahead)
+    }
     fn remain_internal(&self) -> &[u8] {
         unsafe {
             core::slice::from_raw_parts(
@@ -650,54 +1029,192 @@ impl<const N: usize> ReaderBufferTrait for Reader<N> {
     }
 }
 
-#[cfg(all(target_arch = "x86_64", not(test)))]
+/// Reader over an in-memory byte slice.
+///
+/// The unsafe integer parsing routines read a few bytes past the end of the remaining data,
+/// relying on zero padding that a borrowed slice does not have. Hence, the last bytes of the
+/// slice are copied into an internal zero-padded buffer, which is used once the reader gets
+/// close to the end of the slice.
+/// ```
+/// use basm_std::platform::io::{ReaderTrait, SliceReader};
+/// let mut reader = SliceReader::new(b"2 3\n1 2 3\n4 5 6\n");
+/// let (n, m) = (reader.usize(), reader.usize());
+/// let a: Vec<Vec<i32>> = reader.collect_2d(n, m);
+/// assert_eq!(a, [[1, 2, 3], [4, 5, 6]]);
+/// ```
+pub struct SliceReader<'a> {
+    data: &'a [u8],
+    off: usize,
+    head_end: usize,
+    tail_start: usize,
+    in_tail: bool,
+    tail: [u8; SliceReader::TAIL_LEN + 8],
+}
+
+impl<'a> SliceReader<'a> {
+    const TAIL_LEN: usize = 128;
+    /// Bytes after `head_end`; this is the largest `readahead` used by `ReaderTrait` methods.
+    const HEAD_GAP: usize = 64;
+
+    /// Constructs a new `SliceReader` positioned at the start of `data`.
+    ///
+    /// Only the last 128 bytes of `data` (or all of it, if shorter) are copied into the zero-padded buffer;
+    /// the rest is read in place.
+    pub fn new(data: &'a [u8]) -> Self {
+        let tail_start = data.len().saturating_sub(Self::TAIL_LEN);
+        let head_end = data.len().saturating_sub(Self::HEAD_GAP);
+        let mut tail = [0u8; Self::TAIL_LEN + 8];
+        tail[..data.len() - tail_start].copy_from_slice(&data[tail_start..]);
+        Self {
+            data,
+            off: 0,
+            head_end,
+            tail_start,
+            in_tail: head_end == 0,
+            tail,
+        }
+    }
+}
+
+impl ReaderBufferTrait for SliceReader<'_> {
+    fn try_refill_internal(&mut self, readahead: usize) -> usize {
+        if !self.in_tail && self.head_end - self.off < readahead && self.off >= self.tail_start {
+            self.in_tail = true;
+        }
+        self.remain_internal().len()
+    }
+    fn remain_internal(&self) -> &[u8] {
+        if self.in_tail {
+            &self.tail[self.off - self.tail_start..self.data.len() - self.tail_start]
+        } else {
+            &self.data[self.off..self.head_end]
+        }
+    }
+    fn advance(&mut self, bytes: usize) {
+        self.off += bytes;
+        // Switching here ensures that an unsafe 8-byte read at `off` never goes past the slice.
+        if self.off >= self.head_end {
+            self.in_tail = true;
+        }
+    }
+}
+
+/// Reader over an owned byte buffer.
+/// ```
+/// use basm_std::platform::io::{ReaderTrait, VecReader};
+/// let mut reader = VecReader::new(b"hello -42".to_vec());
+/// assert_eq!(reader.word(), "hello");
+/// assert_eq!(reader.i64(), -42);
+/// ```
+pub struct VecReader {
+    buf: Vec<u8>,
+    off: usize,
+}
+
+impl VecReader {
+    /// Constructs a new `VecReader` positioned at the start of `buf`, taking ownership of it.
+    ///
+    /// Eight zero bytes are appended to `buf` as padding, which may reallocate it.
+    pub fn new(mut buf: Vec<u8>) -> Self {
+        buf.extend_from_slice(&[0u8; 8]); // ensure padding for unsafe I/O acceleration
+        Self { buf, off: 0 }
+    }
+}
+
+impl From<Vec<u8>> for VecReader {
+    fn from(buf: Vec<u8>) -> Self {
+        Self::new(buf)
+    }
+}
+
+impl From<String> for VecReader {
+    fn from(s: String) -> Self {
+        Self::new(s.into_bytes())
+    }
+}
+
+impl ReaderBufferTrait for VecReader {
+    fn try_refill_internal(&mut self, _readahead: usize) -> usize {
+        self.buf.len() - 8 - self.off
+    }
+    fn remain_internal(&self) -> &[u8] {
+        &self.buf[self.off..self.buf.len() - 8]
+    }
+    fn advance(&mut self, bytes: usize) {
+        self.off += bytes;
+    }
+}
+
+/// Reader that maps the standard input into memory, avoiding copies.
+///
+/// The standard input is mapped with `mmap` if it is a (nonempty) regular file on Linux.
+/// Otherwise (e.g., if it is a pipe, or on other platforms), the whole input is read
+/// into a growable buffer at construction, so `MmapReader` can be used regardless of the judge.
+#[cfg(not(test))]
 pub struct MmapReader {
     buf: *const u8,
     end: *const u8,
+    _owned: Vec<u8>,
 }
 
-#[cfg(all(target_arch = "x86_64", not(test)))]
+#[cfg(not(test))]
 impl Default for MmapReader {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[cfg(all(target_arch = "x86_64", not(test)))]
+#[cfg(not(test))]
 impl MmapReader {
     pub fn new() -> Self {
-        #[cfg(not(feature = "submit"))]
-        {
-            let pd = crate::platform::services::platform_data();
-            assert!(
-                pd.env_id == crate::platform::services::ENV_ID_LINUX,
-                "MmapReader is only supported on Linux x64"
-            );
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(out) = Self::try_mmap() {
+            return out;
+        }
+        Self::read_all()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn try_mmap() -> Option<Self> {
+        // for `short`, we omit OS check, since it must be Linux
+        #[cfg(not(feature = "short"))]
+        if services::platform_data().env_id != services::ENV_ID_LINUX {
+            return None;
         }
 
         use crate::platform::os::linux::syscall;
         let mut st = syscall::Stat::default();
+        #[allow(clippy::unnecessary_cast)] // `st_mode` is `u16` on x86
         unsafe {
-            syscall::fstat(0, &mut st);
+            if syscall::fstat(0, &mut st) != 0
+                || (st.st_mode as u32 & syscall::S_IFMT) != syscall::S_IFREG
+                || st.st_size == 0
+            {
+                return None;
+            }
             let page_boundary = st.st_size as usize & 0xfff;
             let file_size = ((st.st_size as usize + 0xfff) >> 12) << 12;
             let mut extra_page = 0;
-            if page_boundary > 0xff8 {
+            if page_boundary == 0 || page_boundary > 0xff8 {
                 // Ensure we have at least 8 bytes at the end of the buffer.
-                // If (stdin file size mod 4096) > 4088, we allocate one extra page at the end of it.
+                // If (stdin file size mod 4096) is 0 or > 4088, we allocate one extra page at the end of it.
                 extra_page = 0x1000;
             }
             // We first reserve the required address space.
+            let reserved = file_size + extra_page;
             let buf = syscall::mmap(
                 core::ptr::null(),
-                file_size + extra_page,
+                reserved,
                 syscall::PROT_NONE,
                 syscall::MAP_ANON | syscall::MAP_PRIVATE,
                 -1,
                 0,
             );
+            if syscall::is_err_ptr(buf) {
+                return None;
+            }
             // Then, we allocate the memory for stdin, ...
-            syscall::mmap(
+            let ret = syscall::mmap(
                 buf,
                 st.st_size as usize,
                 syscall::PROT_READ,
@@ -705,9 +1222,13 @@ impl MmapReader {
                 0,
                 0,
             );
+            if syscall::is_err_ptr(ret) {
+                syscall::munmap(buf, reserved);
+                return None;
+            }
             if extra_page > 0 {
                 // ...and the extra page at the end if needed.
-                syscall::mmap(
+                let ret = syscall::mmap(
                     buf.wrapping_add(file_size),
                     extra_page,
                     syscall::PROT_WRITE | syscall::PROT_READ,
@@ -715,16 +1236,51 @@ impl MmapReader {
                     -1,
                     0,
                 );
+                if syscall::is_err_ptr(ret) {
+                    syscall::munmap(buf, reserved);
+                    return None;
+                }
             }
-            Self {
+            Some(Self {
                 buf,
                 end: buf.wrapping_add(st.st_size as usize),
+                _owned: Vec::new(),
+            })
+        }
+    }
+
+    fn read_all() -> Self {
+        let mut data: Vec<u8> = Vec::with_capacity(super::DEFAULT_BUF_SIZE);
+        loop {
+            if data.capacity() - data.len() < super::MIN_BUF_SIZE {
+                data.reserve(data.capacity());
+            }
+            let len = data.len();
+            // Leave 8 bytes for the padding
+            let spare = data.capacity() - len - 8;
+            let n = unsafe {
+                services::read_stdio(
+                    0,
+                    core::slice::from_raw_parts_mut(data.as_mut_ptr().add(len), spare),
+                )
+            };
+            if n == 0 {
+                break;
             }
+            unsafe { data.set_len(len + n) };
+        }
+        let len = data.len();
+        data.extend_from_slice(&[0u8; 8]); // ensure padding for unsafe I/O acceleration
+        let buf = data.as_ptr();
+        Self {
+            buf,
+            end: buf.wrapping_add(len),
+            _owned: data,
         }
     }
 }
 
-#[cfg(all(target_arch = "x86_64", not(test)))]
+#[cfg(not(test))]
 impl ReaderBufferTrait for MmapReader {
     fn try_refill_internal(&mut self, _readahead: usize) -> usize {
         unsafe { self.end.offset_from_unsigned(self.buf) }
@@ -740,42 +1296,11 @@ impl ReaderBufferTrait for MmapReader {
 #[cfg(test)]
 mod test {
     use super::*;
-
-    pub struct MockReader {
-        buf: alloc::vec::Vec<u8>,
-        off: usize,
-    }
-
-    impl Default for MockReader {
-        fn default() -> Self {
-            Self::new(&[])
-        }
-    }
-
-    impl MockReader {
-        fn new(data: &[u8]) -> Self {
-            let mut buf = data.to_vec();
-            buf.extend_from_slice(&[0u8; 8]); // ensure padding for unsafe I/O acceleration
-            Self { buf, off: 0 }
-        }
-    }
-
-    impl ReaderBufferTrait for MockReader {
-        fn try_refill_internal(&mut self, _readahead: usize) -> usize {
-            self.buf.len() - 8 - self.off
-        }
-        fn remain_internal(&self) -> &[u8] {
-            &self.buf[self.off..self.buf.len() - 8]
-        }
-        fn advance(&mut self, bytes: usize) {
-            self.off += bytes;
-            assert!(self.off <= self.buf.len() - 8);
-        }
-    }
+    use crate::platform::io::Counted;
 
     #[test]
     fn read_numbers() {
-        let mut reader = MockReader::new(
+        let mut reader = SliceReader::new(
             b"1234 -56 1234567890 -9223372036854775808 18446744073709551615\n-9999.9999\n",
         );
 
@@ -789,7 +1314,7 @@ mod test {
 
     #[test]
     fn read_scientifi_notation() {
-        let mut reader = MockReader::new(b"1e1\n1e-1\n");
+        let mut reader = SliceReader::new(b"1e1\n1e-1\n");
 
         assert_eq!(reader.f64(), 10.0);
         assert_eq!(reader.f64(), 1e-1);
@@ -797,7 +1322,7 @@ mod test {
 
     #[test]
     fn read_word() {
-        let mut reader = MockReader::new(b"Hello World\r\nBye\n");
+        let mut reader = SliceReader::new(b"Hello World\r\nBye\n");
         let mut buf = [0; 100];
 
         let n = reader.word_buf(&mut buf);
@@ -815,7 +1340,7 @@ mod test {
 
     #[test]
     fn next_until() {
-        let mut reader = MockReader::new(b"Hello World\r\nBye\n");
+        let mut reader = SliceReader::new(b"Hello World\r\nBye\n");
         let mut buf = [0; 100];
 
         let n = reader.line_buf(&mut buf);
@@ -829,7 +1354,7 @@ mod test {
 
     #[test]
     fn read_word_without_terminator() {
-        let mut reader = MockReader::new(b"no-terminator");
+        let mut reader = SliceReader::new(b"no-terminator");
         let mut buf = [0; 100];
 
         let n = reader.word_buf(&mut buf);
@@ -840,7 +1365,7 @@ mod test {
     #[test]
     fn read_word_multiple_space_in_between() {
         // This also affects number reading.
-        let mut reader = MockReader::new(b"1 \nb"); // Trailing space in first line
+        let mut reader = SliceReader::new(b"1 \nb"); // Trailing space in first line
         let mut buf = [0; 100];
 
         let n = reader.word_buf(&mut buf);
@@ -853,15 +1378,245 @@ mod test {
 
     #[test]
     fn skip_white() {
-        let mut reader = MockReader::new(b" \t\x0b\n5\n");
+        let mut reader = SliceReader::new(b" \t\x0b\n5\n");
         assert_eq!(reader.skip_whitespace(), 4);
         assert_eq!(reader.usize(), 5);
     }
 
+    #[test]
+    fn collect_until_eof() {
+        let mut reader = SliceReader::new(b"3 1 2 3\n4 5\n6 7 \n\n");
+        let first: Counted<u32> = reader.next();
+        assert_eq!(first.0, [1, 2, 3]);
+        let rest: Vec<(u32, u32)> = reader.collect_until_eof();
+        assert_eq!(rest, [(4, 5), (6, 7)]);
+        assert!(reader.is_eof());
+
+        let mut reader = SliceReader::new(b"0\n");
+        assert!(reader.next::<Counted<i64>>().is_empty());
+        let rest: Vec<i64> = reader.collect_until_eof();
+        assert!(rest.is_empty());
+    }
+
     #[test]
     fn skip_until() {
-        let mut reader = MockReader::new(b"garbage,5\n");
+        let mut reader = SliceReader::new(b"garbage,5\n");
         assert_eq!(reader.discard(b','), b"garbage".len());
         assert_eq!(reader.usize(), 5);
     }
+
+    #[test]
+    fn slice_reader_matches_vec_reader() {
+        // Numbers of various lengths that cross the boundary to the internal tail buffer
+        let mut data = Vec::new();
+        for i in 0..200u64 {
+            let x = i.wrapping_mul(0x9E3779B97F4A7C15) >> (i % 64);
+            data.extend_from_slice(alloc::format!("{} -{} ", x, x % 1000).as_bytes());
+            if i % 7 == 0 {
+                data.extend_from_slice(b"1.5e3\nword");
+            }
+        }
+        for cut in [0, 1, 7, 8, 9, 63, 64, 65, 127, 128, 129, 1000, data.len()] {
+            let data = &data[..data.len().min(cut)];
+            let mut a = SliceReader::new(data);
+            let mut b = VecReader::new(data.to_vec());
+            while !b.is_eof_skip_whitespace() {
+                assert!(!a.is_eof_skip_whitespace());
+                match b.remain()[0] {
+                    b'-' => assert_eq!(b.i64(), a.i64()),
+                    b'1' if b.remain().get(1) == Some(&b'.') => {
+                        assert_eq!(b.f64().to_bits(), a.f64().to_bits())
+                    }
+                    b'0'..=b'9' => assert_eq!(b.u64(), a.u64()),
+                    _ => assert_eq!(b.word(), a.word()),
+                }
+            }
+            assert!(a.is_eof_skip_whitespace());
+        }
+    }
+
+    #[test]
+    fn slice_reader_collect_2d() {
+        let data = alloc::format!("{}\n", "1 2 3 ".repeat(100));
+        let mut reader = SliceReader::new(data.as_bytes());
+        let a: Vec<Vec<u8>> = reader.collect_2d(100, 3);
+        assert!(a.iter().all(|row| row == &[1, 2, 3]));
+        assert!(reader.is_eof_skip_whitespace());
+        let mut reader = VecReader::from(data);
+        let a: Vec<[u8; 3]> = reader.collect(100);
+        assert!(a.iter().all(|row| row == &[1, 2, 3]));
+        assert!(reader.is_eof_skip_whitespace());
+    }
+
+    #[test]
+    fn read_grid() {
+        let mut reader = SliceReader::new(b"3 4\n.#..\n##.#\n...\n");
+        let (h, w) = (reader.usize(), reader.usize());
+        let grid = reader.grid(h, w);
+        assert_eq!(grid, [&b".#.."[..], b"##.#", b"...\0"]);
+
+        let mut reader = SliceReader::new(b"ab\ncd\nef\n");
+        assert_eq!(reader.grid_flat(3, 2, 3), b"ab\0cd\0ef\0");
+        assert!(reader.is_eof_skip_whitespace());
+
+        let mut reader = SliceReader::new(b"abc\nde\nfghij\nk 5\n");
+        assert_eq!(reader.grid(3, 2), [b"ab", b"de", b"fg"]);
+        assert_eq!(reader.grid_flat(1, 2, 2), b"k\0");
+        assert_eq!(5, reader.u32());
+
+        // Rows are consumed even when nothing is stored
+        let mut reader = SliceReader::new(b"ab\ncd\n7\n");
+        assert_eq!(reader.grid_flat(2, 0, 0), b"");
+        assert_eq!(7, reader.u32());
+    }
+
+    #[test]
+    fn read_bits() {
+        let s: Vec<u8> = (0..300u32)
+            .map(|i| if i.count_ones() % 3 == 0 { b'1' } else { b'0' })
+            .collect();
+        for n in [0, 1, 63, 64, 65, 130, 300] {
+            let mut data = b" \n".to_vec();
+            data.extend_from_slice(&s[..n]);
+            data.extend_from_slice(b" 7");
+            let mut reader = SliceReader::new(&data);
+            let bits = reader.bits(n);
+            assert_eq!(n.div_ceil(64), bits.len());
+            for (i, &c) in s[..n].iter().enumerate() {
+                assert_eq!((c == b'1') as u64, (bits[i / 64] >> (i % 64)) & 1);
+            }
+            assert_eq!(7, reader.u32());
+        }
+        let mut reader = SliceReader::new(b"101 1");
+        assert_eq!(reader.bits(5), [0b101]);
+        assert_eq!(reader.bits(5), [1]);
+    }
+
+    #[test]
+    fn read_radix() {
+        let mut reader = SliceReader::new(b"DeadBeef 0 -1 777 ffffffffffffffff 10000000000000000");
+        assert_eq!(0xdeadbeef, reader.u64_radix(16));
+        assert_eq!(0, reader.u64_radix(16));
+        assert_eq!(0, reader.u64_radix(10));
+        assert_eq!(b'-', reader.byte());
+        assert_eq!(1, reader.u64_radix(8));
+        assert_eq!(0o777, reader.u64_radix(8));
+        assert_eq!(u64::MAX, reader.u64_radix(16));
+        assert_eq!(0, reader.u64_radix(16));
+
+        let mut x = 1u64;
+        let mut data = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..500 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let v = x >> (x % 64);
+            let s = if i % 2 == 0 {
+                alloc::format!("{v:x}")
+            } else {
+                alloc::format!("{v:X}")
+            };
+            // Longer runs wrap around
+            let s = "9aF".repeat(i % 7) + &s;
+            let wrapped = s.bytes().fold(0u64, |acc, c| {
+                acc.wrapping_mul(16) + (c as char).to_digit(16).unwrap() as u64
+            });
+            expected.push(wrapped);
+            data.extend_from_slice(s.as_bytes());
+            data.push(b" \ng"[i % 3]);
+        }
+        let mut reader = SliceReader::new(&data);
+        for (i, &v) in expected.iter().enumerate() {
+            assert_eq!(v, reader.u64_radix(16));
+            if i % 3 == 2 {
+                assert_eq!(b'g', reader.byte());
+            }
+        }
+    }
+
+    #[test]
+    fn read_into_matches_scalar() {
+        let mut data = Vec::new();
+        let mut x = 1u64;
+        for i in 0..3000u64 {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let v = x >> (x % 64);
+            let sep = [" ", "\n", "  ", "\r\n", "\t"][i as usize % 5].as_bytes();
+            data.extend_from_slice(alloc::format!("{}", v).as_bytes());
+            data.extend_from_slice(sep);
+            data.extend_from_slice(alloc::format!("{}", v as i32).as_bytes());
+            data.extend_from_slice(sep);
+        }
+        let mut a = SliceReader::new(&data);
+        let mut b = VecReader::new(data.clone());
+        let mut xs = [0u64; 3];
+        let mut ys = [0i32; 2];
+        for _ in 0..1200 {
+            a.read_u64_into(&mut xs[..1]);
+            a.read_i32_into(&mut ys[..1]);
+            assert_eq!(xs[0], b.u64());
+            assert_eq!(ys[0], b.i32());
+        }
+        let expected: Vec<u64> = (0..5000u64)
+            .map(|i| i.wrapping_mul(x) >> (i % 64))
+            .collect();
+        let text: Vec<u8> = expected
+            .iter()
+            .flat_map(|v| alloc::format!("{v} ").into_bytes())
+            .collect();
+        let mut all = alloc::vec![0u64; expected.len()];
+        SliceReader::new(&text).read_u64_into(&mut all);
+        assert_eq!(expected, all);
+        let mut a = VecReader::new(b"1 2 3 -4 5 -6 ".repeat(100));
+        let mut all = [0i32; 600];
+        a.read_i32_into(&mut all);
+        assert!(all.chunks(6).all(|c| c == [1, 2, 3, -4, 5, -6]));
+        let mut b = SliceReader::new(b"0 18446744073709551615 1234567890123456 999999999999999\n");
+        b.read_u64_into(&mut xs);
+        assert_eq!(xs, [0, u64::MAX, 1234567890123456]);
+        assert_eq!(999999999999999, b.u64());
+
+        // Batches of various sizes over whitespace runs of various lengths
+        let mut data = Vec::new();
+        let mut expected = Vec::new();
+        for i in 0..4000usize {
+            x = x
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let v = (x >> 17) as i32 >> (x % 31);
+            expected.push(v);
+            data.extend_from_slice(alloc::format!("{v}").as_bytes());
+            let gap = [1, 1, 2, 1, 70, 3, 1, 130][i % 8];
+            data.extend((0..gap).map(|j| if j % 3 == 2 { b'\n' } else { b' ' }));
+        }
+        data.extend_from_slice(b"7\nend");
+        let mut a = SliceReader::new(&data);
+        let mut all = alloc::vec![0i32; expected.len()];
+        let mut i = 0;
+        for k in (1..).step_by(7) {
+            let j = (i + k).min(all.len());
+            a.read_i32_into(&mut all[i..j]);
+            i = j;
+            if i == all.len() {
+                break;
+            }
+        }
+        assert_eq!(expected, all);
+        assert_eq!(7, a.i32());
+        assert_eq!("", a.line());
+        assert_eq!("end", a.word());
+
+        // A batch stops right after its last integer
+        let mut data = b"1 -2 3   \nrest of line\n".to_vec();
+        data.extend_from_slice(&[b' '; 200]);
+        let mut a = SliceReader::new(&data);
+        let mut three = [0i32; 3];
+        a.read_i32_into(&mut three);
+        assert_eq!(three, [1, -2, 3]);
+        assert_eq!("   ", a.line());
+        assert_eq!("rest of line", a.line());
+    }
 }
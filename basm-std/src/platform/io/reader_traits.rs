@@ -1,6 +1,7 @@
 use super::{Readable, ReaderTrait};
 use alloc::string::String;
-use core::ops::Deref;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
 
 macro_rules! impl_primitive {
     ($($ty:ident)*) => {
@@ -57,6 +58,38 @@ impl Deref for Nonwhite {
     }
 }
 
+/// A `Vec<T>` preceded by its length in the input.
+///
+/// Reading a `Counted<T>` reads a `usize` `n` followed by `n` elements of type `T`.
+/// ```no_run
+/// use basm_std::platform::io::{Counted, Reader, ReaderTrait};
+/// let mut reader: Reader = Default::default();
+/// let a: Counted<i64> = reader.next(); // "3 1 2 3" => [1, 2, 3]
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Counted<T>(pub Vec<T>);
+
+impl<T: Readable> Readable for Counted<T> {
+    fn read(reader: &mut impl ReaderTrait) -> Self {
+        let n = reader.usize();
+        Self(reader.collect(n))
+    }
+}
+
+impl<T> Deref for Counted<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Counted<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<T: Readable, const N: usize> Readable for [T; N] {
     fn read(reader: &mut impl ReaderTrait) -> Self {
         core::array::from_fn(|_| T::read(reader))
@@ -0,0 +1,269 @@
+use super::ReaderTrait;
+
+/// How the end of the test cases is determined. Used by [`TestCases`] and [`testcases!`](crate::testcases).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaseFormat<'a> {
+    /// The number of test cases `T` is given first.
+    Count,
+    /// Test cases continue until the end of the input.
+    UntilEof,
+    /// Test cases continue until a line equal to the given sentinel (e.g., `"0 0"`), which is consumed.
+    /// The end of the input also ends the test cases.
+    ///
+    /// The sentinel must be at most 63 bytes long, since the readers cannot look ahead further.
+    UntilLine(&'a str),
+}
+
+/// Iterates over the test cases of a multi-testcase input, yielding 1-based case numbers.
+///
+/// Since the reader is also used to read each test case, it is passed to [`TestCases::next_case`]
+/// instead of being borrowed by `TestCases` itself.
+/// ```
+/// use basm_std::platform::io::{CaseFormat, ReaderTrait, SliceReader, TestCases};
+/// let mut reader = SliceReader::new(b"1 2\n3 4\n0 0\n");
+/// let mut cases = TestCases::new(&mut reader, CaseFormat::UntilLine("0 0"));
+/// let mut sums = vec![];
+/// while let Some(_) = cases.next_case(&mut reader) {
+///     sums.push(reader.i32() + reader.i32());
+/// }
+/// assert_eq!(sums, [3, 7]);
+/// ```
+pub struct TestCases<'a> {
+    format: CaseFormat<'a>,
+    case_no: usize,
+    total: usize,
+}
+
+impl<'a> TestCases<'a> {
+    /// Prepares to iterate over the test cases. For [`CaseFormat::Count`], this reads `T`.
+    ///
+    /// Panics if the sentinel of [`CaseFormat::UntilLine`] is longer than 63 bytes.
+    pub fn new<R: ReaderTrait>(reader: &mut R, format: CaseFormat<'a>) -> Self {
+        let total = match format {
+            CaseFormat::Count => reader.usize(),
+            CaseFormat::UntilLine(sentinel) => {
+                assert!(
+                    sentinel.len() < Self::LOOKAHEAD,
+                    "The sentinel line is too long"
+                );
+                usize::MAX
+            }
+            _ => usize::MAX,
+        };
+        Self {
+            format,
+            case_no: 0,
+            total,
+        }
+    }
+    /// Returns the 1-based number of the next test case, or `None` if there are no more test cases.
+    pub fn next_case<R: ReaderTrait>(&mut self, reader: &mut R) -> Option<usize> {
+        if self.case_no >= self.total {
+            return None;
+        }
+        let end = match self.format {
+            CaseFormat::Count => false,
+            CaseFormat::UntilEof => reader.is_eof_skip_whitespace(),
+            CaseFormat::UntilLine(sentinel) => {
+                reader.is_eof_skip_whitespace() || Self::eat_line(reader, sentinel.as_bytes())
+            }
+        };
+        if end {
+            self.total = self.case_no;
+            return None;
+        }
+        self.case_no += 1;
+        Some(self.case_no)
+    }
+    /// Consumes the upcoming line if it is equal to `line`, and returns whether it was.
+    ///
+    /// Nothing is consumed unless the whole line (and the line break or EOF after it) matches.
+    /// The reader is refilled while it makes progress, since a refill may return less than requested
+    /// (e.g., `Reader` does not read when its buffer already holds a whole token).
+    fn eat_line<R: ReaderTrait>(reader: &mut R, line: &[u8]) -> bool {
+        let mut len = usize::MAX;
+        loop {
+            let n = reader.try_refill(line.len() + 1);
+            let data = reader.remain();
+            if !line.starts_with(&data[..data.len().min(line.len())]) {
+                return false;
+            }
+            let matched = match data.get(line.len()) {
+                Some(&c) => c == b'\n' || c == b'\r',
+                // EOF ends the line
+                None if n == len => data.len() == line.len(),
+                None => {
+                    len = n;
+                    continue;
+                }
+            };
+            if matched {
+                reader.try_consume(line.len());
+            }
+            return matched;
+        }
+    }
+    /// The largest `readahead` supported by all readers.
+    const LOOKAHEAD: usize = 64;
+}
+
+/// Runs the body once for each test case, binding the 1-based case number.
+///
+/// By default, the number of test cases `T` is read first. A [`CaseFormat`] can be given
+/// with `format =` to read until EOF or until a sentinel line instead. With `prefix`,
+/// `Case #k: ` is written to the given writer before each test case, as in Google Code Jam.
+/// ```no_run
+/// use basm_std::platform::io::{CaseFormat, Print, Reader, ReaderTrait, Writer};
+/// use basm_std::testcases;
+/// let mut reader: Reader = Default::default();
+/// let mut writer: Writer = Default::default();
+/// testcases!(reader, |_| {
+///     let (a, b) = (reader.i64(), reader.i64());
+///     writer.println(a + b);
+/// });
+/// testcases!(reader, format = CaseFormat::UntilLine("0"), |_| {
+///     writer.println(reader.i64() * 2);
+/// });
+/// testcases!(reader, writer, format = CaseFormat::UntilEof, prefix, |k| {
+///     writer.println(k as i64 * reader.i64()); // Case #1: ...
+/// });
+/// ```
+#[macro_export]
+macro_rules! testcases {
+    ($reader:expr, $writer:expr, prefix, |$k:pat_param| $body:expr) => {
+        $crate::testcases!(
+            $reader,
+            $writer,
+            format = $crate::platform::io::CaseFormat::Count,
+            prefix,
+            |$k| $body
+        )
+    };
+    ($reader:expr, $writer:expr, format = $format:expr, prefix, |$k:pat_param| $body:expr) => {{
+        let mut _cases = $crate::platform::io::TestCases::new(&mut $reader, $format);
+        while let Some(_case_no) = _cases.next_case(&mut $reader) {
+            {
+                use $crate::platform::io::Print;
+                $writer.print("Case #");
+                $writer.print(_case_no);
+                $writer.print(": ");
+            }
+            let $k = _case_no;
+            $body;
+        }
+    }};
+    ($reader:expr, |$k:pat_param| $body:expr) => {
+        $crate::testcases!(
+            $reader,
+            format = $crate::platform::io::CaseFormat::Count,
+            |$k| $body
+        )
+    };
+    ($reader:expr, format = $format:expr, |$k:pat_param| $body:expr) => {{
+        let mut _cases = $crate::platform::io::TestCases::new(&mut $reader, $format);
+        while let Some(_case_no) = _cases.next_case(&mut $reader) {
+            let $k = _case_no;
+            $body;
+        }
+    }};
+    ($reader:expr, $writer:expr, $(format = $format:expr,)? |$k:pat_param| $body:expr) => {
+        $crate::testcases!($reader, $(format = $format,)? |$k| $body)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::platform::io::SliceReader;
+
+    fn sums(input: &[u8], format: CaseFormat) -> Vec<(usize, i32)> {
+        let mut reader = SliceReader::new(input);
+        let mut out = vec![];
+        crate::testcases!(reader, format = format, |k| {
+            let n = reader.usize();
+            out.push((k, (0..n).map(|_| reader.i32()).sum()));
+        });
+        out
+    }
+
+    #[test]
+    fn test_case_formats() {
+        let expected = [(1, 3), (2, -4)];
+        assert_eq!(sums(b"2\n2 1 2\n1 -4\n", CaseFormat::Count), expected);
+        assert_eq!(sums(b"2 1 2\n1 -4\n\n", CaseFormat::UntilEof), expected);
+        assert_eq!(
+            sums(b"2 1 2\n1 -4\n0\n", CaseFormat::UntilLine("0")),
+            expected
+        );
+        assert_eq!(sums(b"2 1 2\n1 -4", CaseFormat::UntilLine("0")), expected);
+        // "02 1 1" merely starts with the sentinel line "0", so it is a test case
+        assert_eq!(
+            sums(b"02 1 1\n0\r\n3 1 1 1\n", CaseFormat::UntilLine("0")),
+            [(1, 2)]
+        );
+        // A line with the longest sentinel as a proper prefix is left intact
+        let long = "0".repeat(63);
+        let input = alloc::format!("1 {long}0\n{long}\n");
+        assert_eq!(
+            sums(input.as_bytes(), CaseFormat::UntilLine(&long)),
+            [(1, 0)]
+        );
+        // ... and a line that is a proper prefix of the sentinel at the end of the input
+        assert_eq!(
+            sums(b"1 5\n0 ", CaseFormat::UntilLine("0 0")),
+            [(1, 5), (2, 0)]
+        );
+        let mut reader = crate::platform::io::VecReader::new(input.clone().into_bytes());
+        let mut cases = TestCases::new(&mut reader, CaseFormat::UntilLine(&long[1..]));
+        assert!(cases.next_case(&mut reader).is_some());
+        assert_eq!(reader.usize(), 1);
+        assert_eq!(reader.word(), alloc::format!("{long}0"));
+        assert!(cases.next_case(&mut reader).is_some());
+        assert_eq!(reader.word(), long);
+        assert!(cases.next_case(&mut reader).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "too long")]
+    fn test_case_long_sentinel() {
+        let long = "9".repeat(64);
+        sums(b"", CaseFormat::UntilLine(&long));
+    }
+
+    #[test]
+    fn test_case_prefix() {
+        use crate::platform::io::{Print, Writer};
+        use crate::platform::testing::run_with_input;
+        let out = run_with_input(b"2\n3\n5\n", || {
+            let mut reader = crate::platform::io::Reader::<128>::new();
+            let mut writer = Writer::<128>::new();
+            crate::testcases!(reader, writer, prefix, |k| {
+                writer.println(reader.i32() * k as i32);
+            });
+        });
+        assert_eq!(out, b"Case #1: 3\nCase #2: 10\n");
+        let out = run_with_input(b"7 8", || {
+            let mut reader = crate::platform::io::Reader::<128>::new();
+            let mut writer = Writer::<128>::new();
+            crate::testcases!(
+                reader,
+                writer,
+                format = CaseFormat::UntilEof,
+                prefix,
+                |_| {
+                    writer.println(reader.i32());
+                }
+            );
+        });
+        assert_eq!(out, b"Case #1: 7\nCase #2: 8\n");
+        // Without `prefix`, the writer is not written to
+        let out = run_with_input(b"2\n3\n5\n", || {
+            let mut reader = crate::platform::io::Reader::<128>::new();
+            let mut writer = Writer::<128>::new();
+            crate::testcases!(reader, writer, |k| {
+                writer.println(reader.i32() * k as i32);
+            });
+        });
+        assert_eq!(out, b"3\n10\n");
+    }
+}
@@ -1,12 +1,54 @@
 use super::Nonwhite;
+use super::float;
 use crate::platform::services;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::fmt::Arguments;
 use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
 
+/// Buffered writer to standard output.
 pub struct Writer<const N: usize = { super::DEFAULT_BUF_SIZE }> {
     buf: [MaybeUninit<u8>; N],
     off: usize,
+    fd: usize,
+}
+
+/// Buffered writer to standard error, for debugging output.
+///
+/// `ErrWriter` dereferences to [`Writer`], so all of its methods (including `Print`) are available.
+/// Note that the output is only written when the buffer is flushed (e.g., when dropped).
+/// ```no_run
+/// use basm_std::platform::io::{ErrWriter, Print};
+/// let mut err = ErrWriter::<128>::new();
+/// err.println("debug"); // debug (to standard error)
+/// ```
+pub struct ErrWriter<const N: usize = { super::DEFAULT_BUF_SIZE }>(Writer<N>);
+
+impl<const N: usize> ErrWriter<N> {
+    /// Constructs a new `ErrWriter` with buffer size `N`.
+    pub fn new() -> Self {
+        Self(Writer::with_fd(2))
+    }
+}
+
+impl<const N: usize> Default for ErrWriter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for ErrWriter<N> {
+    type Target = Writer<N>;
+    fn deref(&self) -> &Writer<N> {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for ErrWriter<N> {
+    fn deref_mut(&mut self) -> &mut Writer<N> {
+        &mut self.0
+    }
 }
 
 impl<const N: usize> Default for Writer<N> {
@@ -75,18 +117,32 @@ impl<const N: usize> Writer<N> {
     /// let mut writer: Writer = Default::default();
     /// ```
     pub fn new() -> Self {
+        Self::with_fd(1)
+    }
+    /// Constructs a new `Writer` that writes to the file descriptor `fd` instead of standard output.
+    pub(super) fn with_fd(fd: usize) -> Self {
         #[allow(clippy::let_unit_value)]
         let _ = Self::DUMMY;
         Self {
             buf: [const { MaybeUninit::uninit() }; N],
             off: 0,
+            fd,
         }
     }
     /// Flushes the buffer of the current `Writer`.
     pub fn flush(&mut self) {
-        services::write_stdio(1, unsafe { self.buf[..self.off].assume_init_ref() });
+        services::write_stdio(self.fd, unsafe { self.buf[..self.off].assume_init_ref() });
         self.off = 0;
     }
+    /// Returns the file descriptor that this `Writer` writes to.
+    #[cfg(target_os = "linux")]
+    pub(super) fn fd(&self) -> usize {
+        self.fd
+    }
+    /// Returns `true` if there is no buffered output waiting to be flushed.
+    pub(super) fn is_empty(&self) -> bool {
+        self.off == 0
+    }
     /// Flushes the buffer of the current `Writer` if readahead plus the current offset exceeds the buffer length,
     /// thereby ensuring that at least `readahead` bytes are available in the buffer.
     pub fn try_flush(&mut self, readahead: usize) {
@@ -221,6 +277,12 @@ impl<const N: usize> Writer<N> {
     #[cfg(target_arch = "x86_64")]
     pub fn u32(&mut self, n: u32) {
         self.try_flush(11 + 8);
+        self.u32_unchecked(n);
+    }
+    // Writes `n` assuming that at least 10 + 8 bytes are available in the buffer.
+    #[cfg(any(not(feature = "short"), feature = "fastio"))]
+    #[cfg(target_arch = "x86_64")]
+    fn u32_unchecked(&mut self, n: u32) {
         let mut p;
         if n < 100_000_000 {
             p = unsafe { cvt8(n) };
@@ -306,6 +368,12 @@ impl<const N: usize> Writer<N> {
     #[cfg(target_arch = "x86_64")]
     pub fn u64(&mut self, n: u64) {
         self.try_flush(21 + 8);
+        self.u64_unchecked(n);
+    }
+    // Writes `n` assuming that at least 20 + 8 bytes are available in the buffer.
+    #[cfg(any(not(feature = "short"), feature = "fastio"))]
+    #[cfg(target_arch = "x86_64")]
+    fn u64_unchecked(&mut self, n: u64) {
         let mut plo;
         if n < 100_000_000 {
             // For small n, we avoid the cost of zero-initializing hi128.
@@ -456,6 +524,31 @@ impl<const N: usize> Writer<N> {
         let printed = buffer.format(f);
         self.bytes(printed.as_bytes());
     }
+    /// Writes a single `f64` to standard output with exactly `digits` digits after the decimal point.
+    ///
+    /// The output is correctly rounded (half to even on the exact binary value, as in C's `printf("%.*f")`),
+    /// regardless of the magnitude of `f`. A value that rounds to zero is written without a minus sign.
+    /// NaN and infinities are written as `NaN`, `inf` and `-inf`.
+    /// ```no_run
+    /// use basm_std::platform::io::Writer;
+    /// let mut writer: Writer = Default::default();
+    /// writer.f64_fixed(1.0 / 3.0, 9); // 0.333333333
+    /// writer.f64_fixed(-0.0001, 3); // 0.000
+    /// ```
+    pub fn f64_fixed(&mut self, f: f64, digits: usize) {
+        float::write_fixed(f, digits, &mut |s: &[u8]| self.bytes(s));
+    }
+    /// Writes a single `f64` to standard output in scientific notation with exactly `digits` digits
+    /// after the decimal point and an exponent of at least two digits (as in C's `printf("%.*e")`).
+    /// ```no_run
+    /// use basm_std::platform::io::Writer;
+    /// let mut writer: Writer = Default::default();
+    /// writer.f64_sci(123456.0, 6); // 1.234560e+05
+    /// writer.f64_sci(-0.0, 2); // -0.00e+00
+    /// ```
+    pub fn f64_sci(&mut self, f: f64, digits: usize) {
+        float::write_sci(f, digits, &mut |s: &[u8]| self.bytes(s));
+    }
     /// Writes a single `char` to standard output, encoded as UTF-8.
     /// ```no_run
     /// use basm_std::platform::io::Writer;
@@ -486,6 +579,162 @@ impl<const N: usize> Writer<N> {
             self.byte_unchecked(0b10000000 | (u & 0x3F) as u8);
         }
     }
+    /// Writes the elements of `iter` to standard output, separated by `sep`.
+    /// No separator is written after the last element, and no newline is added.
+    /// ```no_run
+    /// use basm_std::platform::io::Writer;
+    /// let mut writer: Writer = Default::default();
+    /// writer.print_iter((1..=3).map(|x| x * x), ", "); // 1, 4, 9
+    /// ```
+    pub fn print_iter<I, T>(&mut self, iter: I, sep: &str)
+    where
+        I: IntoIterator<Item = T>,
+        Self: Print<T>,
+    {
+        let mut iter = iter.into_iter();
+        if let Some(x) = iter.next() {
+            self.print(x);
+            for x in iter {
+                self.str(sep);
+                self.print(x);
+            }
+        }
+    }
+    /// Writes the `u32`s in `a` to standard output, separated by `sep`.
+    ///
+    /// This is faster than writing the elements one by one, since space in the buffer is reserved for many elements at once.
+    /// ```no_run
+    /// use basm_std::platform::io::Writer;
+    /// let mut writer: Writer = Default::default();
+    /// writer.u32_slice(&[1, 22, 333], " "); // 1 22 333
+    /// ```
+    pub fn u32_slice(&mut self, a: &[u32], sep: &str) {
+        #[cfg(any(not(feature = "short"), feature = "fastio"))]
+        #[cfg(target_arch = "x86_64")]
+        self.slice_batched(a, sep.as_bytes(), 10, |w, x| w.u32_unchecked(x));
+        #[cfg(any(
+            all(feature = "short", not(feature = "fastio")),
+            not(target_arch = "x86_64")
+        ))]
+        self.print_iter(a, sep);
+    }
+    /// Writes the `i32`s in `a` to standard output, separated by `sep`.
+    /// See [`Writer::u32_slice()`].
+    pub fn i32_slice(&mut self, a: &[i32], sep: &str) {
+        #[cfg(any(not(feature = "short"), feature = "fastio"))]
+        #[cfg(target_arch = "x86_64")]
+        self.slice_batched(a, sep.as_bytes(), 11, |w, x| {
+            if x < 0 {
+                w.byte_unchecked(b'-');
+            }
+            w.u32_unchecked(x.unsigned_abs());
+        });
+        #[cfg(any(
+            all(feature = "short", not(feature = "fastio")),
+            not(target_arch = "x86_64")
+        ))]
+        self.print_iter(a, sep);
+    }
+    /// Writes the `u64`s in `a` to standard output, separated by `sep`.
+    /// See [`Writer::u32_slice()`].
+    pub fn u64_slice(&mut self, a: &[u64], sep: &str) {
+        #[cfg(any(not(feature = "short"), feature = "fastio"))]
+        #[cfg(target_arch = "x86_64")]
+        self.slice_batched(a, sep.as_bytes(), 20, |w, x| w.u64_unchecked(x));
+        #[cfg(any(
+            all(feature = "short", not(feature = "fastio")),
+            not(target_arch = "x86_64")
+        ))]
+        self.print_iter(a, sep);
+    }
+    /// Writes the `i64`s in `a` to standard output, separated by `sep`.
+    /// See [`Writer::u32_slice()`].
+    /// ```no_run
+    /// use basm_std::platform::io::Writer;
+    /// let mut writer: Writer = Default::default();
+    /// writer.i64_slice(&[-1, 0, i64::MIN], "\n"); // -1\n0\n-9223372036854775808
+    /// ```
+    pub fn i64_slice(&mut self, a: &[i64], sep: &str) {
+        #[cfg(any(not(feature = "short"), feature = "fastio"))]
+        #[cfg(target_arch = "x86_64")]
+        self.slice_batched(a, sep.as_bytes(), 21, |w, x| {
+            if x < 0 {
+                w.byte_unchecked(b'-');
+            }
+            w.u64_unchecked(x.unsigned_abs());
+        });
+        #[cfg(any(
+            all(feature = "short", not(feature = "fastio")),
+            not(target_arch = "x86_64")
+        ))]
+        self.print_iter(a, sep);
+    }
+    // Writes the elements of `a` separated by `sep` using `f`, which writes a single element
+    // of at most `width` bytes assuming that enough space (plus 8 bytes) is available in the buffer.
+    #[cfg(any(not(feature = "short"), feature = "fastio"))]
+    #[cfg(target_arch = "x86_64")]
+    fn slice_batched<T: Copy>(
+        &mut self,
+        a: &[T],
+        sep: &[u8],
+        width: usize,
+        mut f: impl FnMut(&mut Self, T),
+    ) {
+        // 8 bytes for the unaligned writes of `cvt8`, and 1 byte for `byte_unchecked` in `println()`
+        const SLACK: usize = 8 + 1;
+        let width = width + sep.len();
+        let mut i = 0;
+        while i < a.len() {
+            let mut cap = (N - self.off).saturating_sub(SLACK) / width;
+            if cap == 0 {
+                self.flush();
+                cap = (N - SLACK) / width;
+                if cap == 0 {
+                    // `sep` is too long to batch
+                    for &x in &a[i..] {
+                        if i > 0 {
+                            self.bytes(sep);
+                        }
+                        self.try_flush(width - sep.len() + SLACK);
+                        f(self, x);
+                        i += 1;
+                    }
+                    break;
+                }
+            }
+            for &x in &a[i..a.len().min(i + cap)] {
+                if i > 0 {
+                    unsafe {
+                        self.buf[self.off..self.off + sep.len()]
+                            .assume_init_mut()
+                            .copy_from_slice(sep);
+                    }
+                    self.off += sep.len();
+                }
+                f(self, x);
+                i += 1;
+            }
+        }
+    }
+    /// Writes a 2D grid to standard output. The elements of each row are separated by `sep`,
+    /// and `row_sep` is written after every row (including the last one).
+    /// ```no_run
+    /// use basm_std::platform::io::Writer;
+    /// let mut writer: Writer = Default::default();
+    /// let grid = [[1, 2], [3, 4]];
+    /// writer.print_grid(&grid, " ", "\n"); // 1 2\n3 4\n
+    /// ```
+    pub fn print_grid<I, R, T>(&mut self, rows: I, sep: &str, row_sep: &str)
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = T>,
+        Self: Print<T>,
+    {
+        for row in rows {
+            self.print_iter(row, sep);
+            self.str(row_sep);
+        }
+    }
 }
 
 pub trait Print<T> {
@@ -568,6 +817,36 @@ impl<const N: usize> Print<&String> for Writer<N> {
     }
 }
 
+/// An `f64` to be written with a fixed number of digits after the decimal point,
+/// using [`Writer::f64_fixed()`]. For example, `writer.println(Fixed(x, 9))`.
+#[derive(Clone, Copy, Debug)]
+pub struct Fixed(pub f64, pub usize);
+
+/// An `f64` to be written in scientific notation with a fixed number of digits after the decimal point,
+/// using [`Writer::f64_sci()`]. For example, `writer.println(Sci(x, 6))`.
+#[derive(Clone, Copy, Debug)]
+pub struct Sci(pub f64, pub usize);
+
+impl<const N: usize> Print<Fixed> for Writer<N> {
+    fn print(&mut self, x: Fixed) {
+        self.f64_fixed(x.0, x.1);
+    }
+    fn println(&mut self, x: Fixed) {
+        self.f64_fixed(x.0, x.1);
+        self.byte_unchecked(b'\n');
+    }
+}
+
+impl<const N: usize> Print<Sci> for Writer<N> {
+    fn print(&mut self, x: Sci) {
+        self.f64_sci(x.0, x.1);
+    }
+    fn println(&mut self, x: Sci) {
+        self.f64_sci(x.0, x.1);
+        self.byte_unchecked(b'\n');
+    }
+}
+
 macro_rules! impl_print{
     ($($ty:ident)*) => {
         $(
@@ -604,6 +883,97 @@ macro_rules! impl_print{
 
 impl_print!(i8 u8 i16 u16 i32 u32 i64 u64 f64 i128 u128 isize usize char);
 
+// Note: `&[u8]`, `&[u8; M]` and `Vec<u8>` are excluded from the following since
+// byte slices are written as raw bytes (see `Print<&[u8]>` above).
+macro_rules! impl_print_seq {
+    ($($ty:ty => $write:ident),*) => {
+        $(
+            /// Writes the elements separated by a single space.
+            impl<const N: usize> Print<&[$ty]> for Writer<N> {
+                fn print(&mut self, x: &[$ty]) {
+                    self.$write(x, " ");
+                }
+                fn println(&mut self, x: &[$ty]) {
+                    self.$write(x, " ");
+                    self.byte(b'\n');
+                }
+            }
+            /// Writes the elements separated by a single space.
+            impl<const N: usize, const M: usize> Print<&[$ty; M]> for Writer<N> {
+                fn print(&mut self, x: &[$ty; M]) {
+                    self.print(&x[..]);
+                }
+                fn println(&mut self, x: &[$ty; M]) {
+                    self.println(&x[..]);
+                }
+            }
+            /// Writes the elements separated by a single space.
+            impl<const N: usize> Print<Vec<$ty>> for Writer<N> {
+                fn print(&mut self, x: Vec<$ty>) {
+                    self.print(&x[..]);
+                }
+                fn println(&mut self, x: Vec<$ty>) {
+                    self.println(&x[..]);
+                }
+            }
+            /// Writes the elements separated by a single space.
+            impl<const N: usize> Print<&Vec<$ty>> for Writer<N> {
+                fn print(&mut self, x: &Vec<$ty>) {
+                    self.print(&x[..]);
+                }
+                fn println(&mut self, x: &Vec<$ty>) {
+                    self.println(&x[..]);
+                }
+            }
+        )*
+    }
+}
+
+impl_print_seq!(
+    i8 => print_iter,
+    i16 => print_iter,
+    u16 => print_iter,
+    i32 => i32_slice,
+    u32 => u32_slice,
+    i64 => i64_slice,
+    u64 => u64_slice,
+    f64 => print_iter,
+    i128 => print_iter,
+    u128 => print_iter,
+    isize => print_iter,
+    usize => print_iter,
+    char => print_iter,
+    String => print_iter
+);
+
+macro_rules! impl_print_tuple {
+    ($u:ident) => {};
+    ($u:ident $($t:ident)+) => {
+        /// Writes the elements separated by a single space.
+        impl<const N: usize, $u, $($t),+> Print<($u, $($t),+)> for Writer<N>
+        where
+            Self: Print<$u> $(+ Print<$t>)+,
+        {
+            #[allow(non_snake_case)]
+            fn print(&mut self, x: ($u, $($t),+)) {
+                let ($u, $($t),+) = x;
+                self.print($u);
+                $(
+                    self.byte(b' ');
+                    self.print($t);
+                )+
+            }
+            fn println(&mut self, x: ($u, $($t),+)) {
+                self.print(x);
+                self.byte(b'\n');
+            }
+        }
+        impl_print_tuple!($($t) +);
+    };
+}
+
+impl_print_tuple!(A B C D E F G H I J K L M O P Q R S T U V W X Y Z);
+
 impl<'a, const N: usize> Print<Arguments<'a>> for Writer<N> {
     fn print(&mut self, x: Arguments<'a>) {
         if let Some(s) = x.as_str() {
@@ -638,78 +1008,278 @@ macro_rules! bprintln {
     }};
 }
 
-/*
+/// Writes the arguments to standard error using [`Print`], separated by a space.
+/// This is a no-op (the arguments are not even evaluated) when the `submit` feature is enabled.
+/// ```no_run
+/// use basm_std::beprint;
+/// let (n, m) = (3, 4);
+/// beprint!("n, m =", n, m); // n, m = 3 4 (to standard error)
+/// ```
+#[cfg(not(feature = "submit"))]
+#[macro_export]
+macro_rules! beprint {
+    ($($arg:expr),* $(,)?) => {{
+        let mut _err = $crate::platform::io::ErrWriter::<256>::new();
+        $crate::__beprint_args!(_err, $($arg),*);
+    }};
+}
+
+/// Writes the arguments to standard error using [`Print`], separated by a space, followed by a newline.
+/// This is a no-op (the arguments are not even evaluated) when the `submit` feature is enabled.
+#[cfg(not(feature = "submit"))]
+#[macro_export]
+macro_rules! beprintln {
+    ($($arg:expr),* $(,)?) => {{
+        let mut _err = $crate::platform::io::ErrWriter::<256>::new();
+        $crate::__beprint_args!(_err, $($arg),*);
+        _err.byte(b'\n');
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __beprint_args {
+    ($err:ident $(,)?) => {};
+    ($err:ident, $first:expr $(, $arg:expr)*) => {{
+        use $crate::platform::io::Print;
+        $err.print($first);
+        $(
+            $err.byte(b' ');
+            $err.print($arg);
+        )*
+    }};
+}
+
+/// Writes `[file:line] expr = value` to standard error for each argument, like `std::dbg!`,
+/// and returns the value of the argument (or a tuple of them for multiple arguments).
+/// The values are written using their `Debug` implementations.
+///
+/// When the `submit` feature is enabled, this only evaluates and returns the arguments.
+/// ```no_run
+/// use basm_std::basm_dbg;
+/// let a = basm_dbg!(1 + 2) * 2; // [src/solution.rs:2] 1 + 2 = 3 (to standard error)
+/// ```
+#[cfg(not(feature = "submit"))]
+#[macro_export]
+macro_rules! basm_dbg {
+    () => {{
+        let mut _err = $crate::platform::io::ErrWriter::<256>::new();
+        $crate::__basm_dbg_location!(_err);
+        _err.byte(b'\n');
+    }};
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                use $crate::platform::io::Print;
+                let mut _err = $crate::platform::io::ErrWriter::<256>::new();
+                $crate::__basm_dbg_location!(_err);
+                _err.print(core::stringify!($val));
+                _err.print(" = ");
+                _err.println(core::format_args!("{:?}", &tmp));
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::basm_dbg!($val)),+,)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __basm_dbg_location {
+    ($err:ident) => {{
+        use $crate::platform::io::Print;
+        $err.print("[");
+        $err.print(core::file!());
+        $err.print(":");
+        $err.print(core::line!());
+        $err.print("] ");
+    }};
+}
+
+#[cfg(feature = "submit")]
+#[macro_export]
+macro_rules! beprint {
+    ($($arg:expr),* $(,)?) => {};
+}
+
+#[cfg(feature = "submit")]
+#[macro_export]
+macro_rules! beprintln {
+    ($($arg:expr),* $(,)?) => {};
+}
+
+#[cfg(feature = "submit")]
+#[macro_export]
+macro_rules! basm_dbg {
+    () => {};
+    ($val:expr $(,)?) => {
+        $val
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($val),+,)
+    };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::platform::testing::run_with_input;
+    use alloc::format;
 
-    #[test]
-    fn write_numbers_without_flush() {
-        clear_stdout();
-        let mut writer = Writer::<100>::new();
+    /// Returns what `f` writes to standard output through a `Writer<N>` that is dropped afterwards.
+    fn capture<const N: usize>(f: impl FnOnce(&mut Writer<N>)) -> String {
+        let out = run_with_input(b"", || f(&mut Writer::<N>::new()));
+        String::from_utf8(out).unwrap()
+    }
 
-        writer.write_usize(10);
-        writer.write_usize(20);
-        assert_eq!(get_stdout_content(), b""); // not flushed yet
+    #[test]
+    fn write_without_flush() {
+        let out = run_with_input(b"", || {
+            let mut writer = Writer::<128>::new();
+            writer.usize(10);
+            writer.usize(20);
+            core::mem::forget(writer);
+        });
+        assert_eq!(out, b""); // not flushed yet
     }
 
     #[test]
-    fn write_numbers_with_explicit_flush() {
-        clear_stdout();
-        let mut writer = Writer::<100>::new();
+    fn write_with_flush() {
+        let out = run_with_input(b"", || {
+            let mut writer = Writer::<128>::new();
+            writer.usize(10);
+            writer.usize(20);
+            writer.flush();
+            writer.usize(30);
+            core::mem::forget(writer);
+        });
+        assert_eq!(out, b"1020");
 
-        writer.write_usize(10);
-        writer.write_usize(20);
-        writer.flush();
-        assert_eq!(get_stdout_content(), b"1020");
+        // A full buffer is flushed as a whole: `byte` keeps one byte spare for `println`,
+        // and `bytes` fills the buffer to the end before flushing it
+        let all: Vec<u8> = (0..200).map(|i| b'a' + i % 26).collect();
+        let out = run_with_input(b"", || {
+            let mut writer = Writer::<128>::new();
+            all.iter().for_each(|&b| writer.byte(b));
+            core::mem::forget(writer);
+        });
+        assert_eq!(out, all[..127]);
+        let out = run_with_input(b"", || {
+            let mut writer = Writer::<128>::new();
+            writer.bytes(&all);
+            writer.bytes(&all);
+            core::mem::forget(writer);
+        });
+        assert_eq!(out, [&all[..], &all[..184]].concat());
     }
 
     #[test]
-    fn write_numbers_implicit_flush() {
-        clear_stdout();
-        let mut writer = Writer::<4>::new();
-
-        writer.write_usize(10);
-        writer.write_usize(20);
-        writer.write_usize(3);
-        assert_eq!(get_stdout_content(), b"1020");
+    fn print() {
+        let out = capture::<128>(|w| {
+            w.print(123usize);
+            w.print(" ");
+            w.print(45i32);
+            w.print(&b" "[..]);
+            w.print(78.9_f64);
+            w.print(' ');
+            w.println(-1i64);
+            w.println("str");
+            w.println(String::from("string"));
+        });
+        assert_eq!(out, "123 45 78.9 -1\nstr\nstring\n");
     }
 
     #[test]
-    fn write_f64() {
-        clear_stdout();
-        let mut writer = Writer::<100>::new();
+    fn print_fixed_and_sci() {
+        let out = capture::<128>(|w| {
+            w.print(Fixed(1.0 / 3.0, 3));
+            w.print(' ');
+            w.println(Fixed(-0.0, 2));
+            w.print(Sci(-1234.5, 2));
+            w.print(' ');
+            w.println(Sci(-0.0, 2));
+        });
+        assert_eq!(out, "0.333 0.00\n-1.23e+03 -0.00e+00\n");
+    }
 
-        writer.write_f64(1.23);
-        writer.write_f64(-0.001);
-        writer.flush();
-        assert_eq!(get_stdout_content(), b"1.23-0.001");
+    #[test]
+    fn print_sequences() {
+        let out = capture::<128>(|w| {
+            w.println((1, "two", 3.5, 'c'));
+            w.println(&[1i32, -2, 3][..]);
+            w.println(&[4u64, 5]);
+            w.println(vec![6i64, -7]);
+            w.println(&vec![String::from("a"), String::from("b")]);
+            w.println(&[1.5f64, 2.0][..]);
+            w.println(&Vec::<u32>::new());
+            w.println(&b"raw"[..]);
+        });
+        assert_eq!(out, "1 two 3.5 c\n1 -2 3\n4 5\n6 -7\na b\n1.5 2.0\n\nraw\n");
     }
 
     #[test]
-    fn print() {
-        clear_stdout();
-        let mut writer = Writer::<100>::new();
-        writer.print(123usize);
-        writer.print(" ");
-        writer.print(45i32);
-        writer.print(b" ");
-        writer.print(78.9_f64);
-        writer.flush();
-        assert_eq!(get_stdout_content(), b"123 45 78.9");
+    fn print_iter_and_grid() {
+        let out = capture::<128>(|w| {
+            w.print_iter((1..=3).map(|x| x * x), ", ");
+            w.print_iter(core::iter::empty::<i32>(), ", ");
+            w.byte(b'|');
+            w.print_iter(["x"], "-");
+            w.byte(b'\n');
+            w.print_grid([[1, 2], [3, 4]], " ", "\n");
+            w.print_grid(["ab".chars(), "".chars()], ".", ";");
+        });
+        assert_eq!(out, "1, 4, 9|x\n1 2\n3 4\na.b;;");
     }
 
     #[test]
-    fn println() {
-        clear_stdout();
-        let mut writer = Writer::<100>::new();
-        writer.println(123usize);
-        writer.println(45i32);
-        writer.println(78.9_f64);
-        writer.println("str");
-        writer.println(b"bytes");
-        writer.flush();
-        assert_eq!(get_stdout_content(), b"123\n45\n78.9\nstr\nbytes\n");
-    }
-}
-*/
+    fn print_slices_across_buffer_boundaries() {
+        let u: Vec<u64> = (0..200).map(|i| u64::MAX >> (i % 64)).collect();
+        let s: Vec<i64> = u.iter().map(|&x| (x as i64).wrapping_neg()).collect();
+        let u32s: Vec<u32> = u.iter().map(|&x| x as u32).collect();
+        let i32s: Vec<i32> = u.iter().map(|&x| x as i32).collect();
+        fn join<T: core::fmt::Display>(a: &[T], sep: &str) -> String {
+            a.iter()
+                .map(|x| format!("{x}"))
+                .collect::<Vec<_>>()
+                .join(sep)
+        }
+        // A separator longer than the slack, and one too long to batch within the buffer
+        let long = "=".repeat(110);
+        for sep in ["", " ", ", ", "<------->", "<--------->", long.as_str()] {
+            // Start at every offset, so that the batches end at different places in the buffer
+            for pad in 0..128 {
+                let out = capture::<128>(|w| {
+                    w.bytes(&[b'.'; 128][..pad]);
+                    w.u64_slice(&u, sep);
+                    w.byte(b'\n');
+                    w.i64_slice(&s, sep);
+                    w.byte(b'\n');
+                    w.u32_slice(&u32s, sep);
+                    w.byte(b'\n');
+                    w.i32_slice(&i32s, sep);
+                });
+                let expected = format!(
+                    "{}{}\n{}\n{}\n{}",
+                    ".".repeat(pad),
+                    join(&u, sep),
+                    join(&s, sep),
+                    join(&u32s, sep),
+                    join(&i32s, sep)
+                );
+                assert_eq!(out, expected, "sep {sep:?}, pad {pad}");
+            }
+        }
+        let out = capture::<128>(|w| {
+            for x in &i32s[..20] {
+                w.println(&[*x; 5]);
+            }
+        });
+        let expected: String = i32s[..20]
+            .iter()
+            .map(|x| format!("{}\n", join(&[*x; 5], " ")))
+            .collect();
+        assert_eq!(out, expected);
+    }
+}
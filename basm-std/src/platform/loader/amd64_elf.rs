@@ -48,18 +48,35 @@ There are currently three files licensed under GPLv2+:
 */
 
 #![allow(clippy::cmp_null)]
+// for `short`, only R_X86_64_RELATIVE is supported
+#![cfg_attr(feature = "short", allow(dead_code))]
 
 use core::mem::MaybeUninit;
 
 // Dynamic section entry types
+const DT_PLTRELSZ: u64 = 2;
+const DT_SYMTAB: u64 = 6;
 const DT_RELA: u64 = 7;
 const DT_RELASZ: u64 = 8;
 const DT_RELAENT: u64 = 9;
+const DT_JMPREL: u64 = 23;
+const DT_RELRSZ: u64 = 35;
+const DT_RELR: u64 = 36;
+
+// The number of dynamic section entry types we keep track of (i.e., the largest d_tag we need plus one)
+#[cfg(feature = "short")]
+const DT_TABLE_LEN: usize = 10;
+#[cfg(not(feature = "short"))]
+const DT_TABLE_LEN: usize = 37;
 
 // Relocation types
 #[allow(dead_code)]
 const R_X86_64_NONE: u32 = 0; // none
+const R_X86_64_64: u32 = 1; // word64   S + A
+const R_X86_64_GLOB_DAT: u32 = 6; // word64   S
+const R_X86_64_JUMP_SLOT: u32 = 7; // word64   S
 const R_X86_64_RELATIVE: u32 = 8; // word64   B + A
+const R_X86_64_IRELATIVE: u32 = 37; // word64   indirect (B + A)
 
 // ELF structs
 #[repr(C, packed)]
@@ -73,14 +90,32 @@ struct Elf64Rela {
     r_info: u64,
     r_addend: u64,
 }
+#[allow(dead_code)]
+#[repr(C, packed)]
+struct Elf64Sym {
+    st_name: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+    st_value: u64,
+    st_size: u64,
+}
 
 pub unsafe extern "sysv64" fn relocate(addr_image_base: u64, addr_dynamic_section: u64) {
     unsafe {
         // We avoid using the match statement to reduce code size (since match generates a jump table)
         let mut ptr_dyn: *const Elf64Dyn = addr_dynamic_section as *const Elf64Dyn;
-        let mut ptr_table = [MaybeUninit::<u64>::uninit(); 10];
+        let mut ptr_table = [MaybeUninit::<u64>::uninit(); DT_TABLE_LEN];
         ptr_table[DT_RELA as usize].write(0);
         ptr_table[DT_RELASZ as usize].write(0);
+        #[cfg(not(feature = "short"))]
+        {
+            ptr_table[DT_PLTRELSZ as usize].write(0);
+            ptr_table[DT_SYMTAB as usize].write(0);
+            ptr_table[DT_JMPREL as usize].write(0);
+            ptr_table[DT_RELRSZ as usize].write(0);
+            ptr_table[DT_RELR as usize].write(0);
+        }
         loop {
             // d_tag will typically reside within u32 range.
             // (ref: https://docs.oracle.com/cd/E19683-01/816-1386/chapter6-42444/index.html)
@@ -94,6 +129,15 @@ pub unsafe extern "sysv64" fn relocate(addr_image_base: u64, addr_dynamic_sectio
             ptr_dyn = ptr_dyn.add(1);
         }
 
+        /* Packed relative relocations (DT_RELR) are applied first,
+         * since the resolvers of R_X86_64_IRELATIVE may depend on them. */
+        #[cfg(not(feature = "short"))]
+        relocate_relr(
+            addr_image_base,
+            ptr_table[DT_RELR as usize].assume_init(),
+            ptr_table[DT_RELRSZ as usize].assume_init(),
+        );
+
         /* 1) Do not use .is_null() since the method itself requires relocations, at least in debug mode.
          * 2) When DT_RELA is present, the other entries DT_RELASZ and DT_RELAENT must exist.
          *    Source: https://docs.oracle.com/cd/E19683-01/817-3677/chapter6-42444/index.html
@@ -121,15 +165,99 @@ pub unsafe extern "sysv64" fn relocate(addr_image_base: u64, addr_dynamic_sectio
                     core::hint::unreachable_unchecked()
                 }
                 #[cfg(not(feature = "short"))]
-                if ul_info as u32 == R_X86_64_NONE {
-                    /* do nothing */
-                } else {
-                    /* not implemented */
-                    panic!();
-                }
+                relocate_other(
+                    addr_image_base,
+                    ptr_table[DT_SYMTAB as usize].assume_init(),
+                    pst_rela,
+                );
             }
             ptr_rela += ptr_table[DT_RELAENT as usize].assume_init();
         }
+
+        /* DT_JMPREL holds the relocations for PLT and IPLT (which contains R_X86_64_IRELATIVE
+         * in static-pie binaries). They always use the same format as DT_RELA on amd64. */
+        #[cfg(not(feature = "short"))]
+        {
+            let mut ptr_rela = addr_image_base + ptr_table[DT_JMPREL as usize].assume_init();
+            let relasz = ptr_rela + ptr_table[DT_PLTRELSZ as usize].assume_init();
+            while ptr_rela < relasz {
+                relocate_other(
+                    addr_image_base,
+                    ptr_table[DT_SYMTAB as usize].assume_init(),
+                    ptr_rela as *mut Elf64Rela,
+                );
+                ptr_rela += core::mem::size_of::<Elf64Rela>() as u64;
+            }
+        }
+    }
+}
+
+/// Applies the packed relative relocations in DT_RELR.
+/// Each even entry is the offset of the next word to relocate, and each odd entry
+/// is a bitmap of the words to relocate among the 63 words that follow.
+#[cfg(not(feature = "short"))]
+unsafe fn relocate_relr(addr_image_base: u64, relr: u64, relrsz: u64) {
+    unsafe {
+        let mut ptr_relr = addr_image_base + relr;
+        let relr_end = ptr_relr + relrsz;
+        let mut ptr_target: *mut u64 = core::ptr::null_mut();
+        while ptr_relr < relr_end {
+            let entry = *(ptr_relr as *const u64);
+            if entry & 1 == 0 {
+                ptr_target = (addr_image_base + entry) as *mut u64;
+                *ptr_target += addr_image_base;
+                ptr_target = ptr_target.add(1);
+            } else {
+                let mut bitmap = entry >> 1;
+                let mut ptr = ptr_target;
+                while bitmap != 0 {
+                    if bitmap & 1 != 0 {
+                        *ptr += addr_image_base;
+                    }
+                    bitmap >>= 1;
+                    ptr = ptr.add(1);
+                }
+                ptr_target = ptr_target.add(63);
+            }
+            ptr_relr += 8;
+        }
+    }
+}
+
+/// Applies a relocation other than R_X86_64_RELATIVE.
+/// Symbols must be defined within the binary, since there is nothing to link against.
+#[cfg(not(feature = "short"))]
+unsafe fn relocate_other(addr_image_base: u64, addr_symtab: u64, pst_rela: *const Elf64Rela) {
+    unsafe {
+        let ul_offset = (*pst_rela).r_offset;
+        let ul_info = (*pst_rela).r_info;
+        let l_addend = (*pst_rela).r_addend;
+        let r_type = ul_info as u32;
+        let ptr_target = (addr_image_base + ul_offset) as *mut u64;
+        if r_type == R_X86_64_RELATIVE {
+            *ptr_target = addr_image_base + l_addend;
+        } else if r_type == R_X86_64_64
+            || r_type == R_X86_64_GLOB_DAT
+            || r_type == R_X86_64_JUMP_SLOT
+        {
+            let pst_sym = (addr_image_base + addr_symtab) as *const Elf64Sym;
+            let pst_sym = pst_sym.add((ul_info >> 32) as usize);
+            if (*pst_sym).st_shndx == 0 {
+                /* undefined symbol */
+                panic!();
+            }
+            let l_addend = if r_type == R_X86_64_64 { l_addend } else { 0 };
+            *ptr_target = addr_image_base + (*pst_sym).st_value + l_addend;
+        } else if r_type == R_X86_64_IRELATIVE {
+            let resolver: extern "sysv64" fn() -> u64 =
+                core::mem::transmute((addr_image_base + l_addend) as usize);
+            *ptr_target = resolver();
+        } else if r_type == R_X86_64_NONE {
+            /* do nothing */
+        } else {
+            /* not implemented */
+            panic!();
+        }
     }
 }
 
@@ -50,13 +50,22 @@ There are currently three files licensed under GPLv2+:
 #![allow(clippy::cmp_null)]
 
 // Dynamic section entry types
+const DT_PLTRELSZ: u32 = 2;
+const DT_SYMTAB: u32 = 6;
 const DT_REL: u32 = 17;
 const DT_RELSZ: u32 = 18;
 const DT_RELENT: u32 = 19;
+const DT_JMPREL: u32 = 23;
+const DT_RELRSZ: u32 = 35;
+const DT_RELR: u32 = 36;
 
 // Relocation types
 const R_386_NONE: u8 = 0; // none
-const R_386_RELATIVE: u8 = 8; // word64   B + A
+const R_386_32: u8 = 1; // word32   S + A
+const R_386_GLOB_DAT: u8 = 6; // word32   S
+const R_386_JMP_SLOT: u8 = 7; // word32   S
+const R_386_RELATIVE: u8 = 8; // word32   B + A
+const R_386_IRELATIVE: u8 = 42; // word32   indirect (B + A)
 
 // ELF structs
 #[repr(C, packed)]
@@ -69,6 +78,16 @@ struct Elf32Rel {
     r_offset: u32,
     r_info: u32,
 }
+#[allow(dead_code)]
+#[repr(C, packed)]
+struct Elf32Sym {
+    st_name: u32,
+    st_value: u32,
+    st_size: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+}
 
 unsafe fn find_tag(mut ptr: *const Elf32Dyn, tag: u32) -> *const Elf32Dyn {
     unsafe {
@@ -85,33 +104,129 @@ unsafe fn find_tag(mut ptr: *const Elf32Dyn, tag: u32) -> *const Elf32Dyn {
 pub unsafe extern "C" fn relocate(addr_image_base: u32, addr_dynamic_section: u32) {
     unsafe {
         let ptr_dyn = addr_dynamic_section as *const Elf32Dyn;
+        let ptr_symtab = find_tag(ptr_dyn, DT_SYMTAB);
+        let addr_symtab = if core::ptr::eq(ptr_symtab, core::ptr::null()) {
+            0
+        } else {
+            (*ptr_symtab).d_val_or_ptr
+        };
+
+        /* Packed relative relocations (DT_RELR) are applied first,
+         * since the resolvers of R_386_IRELATIVE may depend on them. */
+        let ptr_relr = find_tag(ptr_dyn, DT_RELR);
+        let ptr_relrsz = find_tag(ptr_dyn, DT_RELRSZ);
+        if !core::ptr::eq(ptr_relr, core::ptr::null())
+            && !core::ptr::eq(ptr_relrsz, core::ptr::null())
+        {
+            relocate_relr(
+                addr_image_base,
+                (*ptr_relr).d_val_or_ptr,
+                (*ptr_relrsz).d_val_or_ptr,
+            );
+        }
+
+        /* do not use .is_null() since the method itself requires relocations, at least in debug mode */
         let ptr_rel = find_tag(ptr_dyn, DT_REL);
         let ptr_relsz = find_tag(ptr_dyn, DT_RELSZ);
         let ptr_relent = find_tag(ptr_dyn, DT_RELENT);
+        if !core::ptr::eq(ptr_rel, core::ptr::null())
+            && !core::ptr::eq(ptr_relsz, core::ptr::null())
+            && !core::ptr::eq(ptr_relent, core::ptr::null())
+        {
+            relocate_rel(
+                addr_image_base,
+                addr_symtab,
+                (*ptr_rel).d_val_or_ptr,
+                (*ptr_relsz).d_val_or_ptr,
+                (*ptr_relent).d_val_or_ptr,
+            );
+        }
 
-        /* do not use .is_null() since the method itself requires relocations, at least in debug mode */
-        if core::ptr::eq(ptr_rel, core::ptr::null())
-            || core::ptr::eq(ptr_relsz, core::ptr::null())
-            || core::ptr::eq(ptr_relent, core::ptr::null())
+        /* DT_JMPREL holds the relocations for PLT and IPLT (which contains R_386_IRELATIVE
+         * in static-pie binaries). They always use the same format as DT_REL on i686. */
+        let ptr_jmprel = find_tag(ptr_dyn, DT_JMPREL);
+        let ptr_pltrelsz = find_tag(ptr_dyn, DT_PLTRELSZ);
+        if !core::ptr::eq(ptr_jmprel, core::ptr::null())
+            && !core::ptr::eq(ptr_pltrelsz, core::ptr::null())
         {
-            return;
+            relocate_rel(
+                addr_image_base,
+                addr_symtab,
+                (*ptr_jmprel).d_val_or_ptr,
+                (*ptr_pltrelsz).d_val_or_ptr,
+                core::mem::size_of::<Elf32Rel>() as u32,
+            );
         }
+    }
+}
 
+/// Applies the packed relative relocations in DT_RELR.
+/// Each even entry is the offset of the next word to relocate, and each odd entry
+/// is a bitmap of the words to relocate among the 31 words that follow.
+unsafe fn relocate_relr(addr_image_base: u32, relr: u32, relrsz: u32) {
+    unsafe {
+        let mut ptr_target: *mut u32 = core::ptr::null_mut();
+        let mut j = 0;
+        while j < relrsz {
+            let entry = *((addr_image_base + relr + j) as *const u32);
+            if entry & 1 == 0 {
+                ptr_target = (addr_image_base + entry) as *mut u32;
+                *ptr_target += addr_image_base;
+                ptr_target = ptr_target.add(1);
+            } else {
+                let mut bitmap = entry >> 1;
+                let mut ptr = ptr_target;
+                while bitmap != 0 {
+                    if bitmap & 1 != 0 {
+                        *ptr += addr_image_base;
+                    }
+                    bitmap >>= 1;
+                    ptr = ptr.add(1);
+                }
+                ptr_target = ptr_target.add(31);
+            }
+            j += 4;
+        }
+    }
+}
+
+/// Applies the relocations in a DT_REL or DT_JMPREL table.
+/// Symbols must be defined within the binary, since there is nothing to link against.
+unsafe fn relocate_rel(addr_image_base: u32, addr_symtab: u32, rel: u32, relsz: u32, relent: u32) {
+    unsafe {
         let mut j = 0;
-        while j < (*ptr_relsz).d_val_or_ptr {
-            let pst_rel = (addr_image_base + (*ptr_rel).d_val_or_ptr + j) as *mut Elf32Rel;
+        while j < relsz {
+            let pst_rel = (addr_image_base + rel + j) as *mut Elf32Rel;
             let ul_offset = (*pst_rel).r_offset;
             let ul_info = (*pst_rel).r_info;
-            if ul_info as u8 == R_386_RELATIVE {
-                let ptr_target = (addr_image_base + ul_offset) as *mut u32;
+            let r_type = ul_info as u8;
+            let ptr_target = (addr_image_base + ul_offset) as *mut u32;
+            if r_type == R_386_RELATIVE {
                 *ptr_target += addr_image_base;
-            } else if ul_info as u8 == R_386_NONE {
+            } else if r_type == R_386_32 || r_type == R_386_GLOB_DAT || r_type == R_386_JMP_SLOT {
+                let pst_sym = (addr_image_base + addr_symtab) as *const Elf32Sym;
+                let pst_sym = pst_sym.add((ul_info >> 8) as usize);
+                if (*pst_sym).st_shndx == 0 {
+                    /* undefined symbol */
+                    panic!();
+                }
+                let value = addr_image_base + (*pst_sym).st_value;
+                if r_type == R_386_32 {
+                    *ptr_target += value;
+                } else {
+                    *ptr_target = value;
+                }
+            } else if r_type == R_386_IRELATIVE {
+                let resolver: extern "C" fn() -> u32 =
+                    core::mem::transmute((addr_image_base + *ptr_target) as usize);
+                *ptr_target = resolver();
+            } else if r_type == R_386_NONE {
                 /* do nothing */
             } else {
                 /* not implemented */
                 panic!();
             }
-            j += (*ptr_relent).d_val_or_ptr;
+            j += relent;
         }
     }
 }
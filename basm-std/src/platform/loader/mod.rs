@@ -6,3 +6,5 @@ pub mod amd64_elf;
 pub mod amd64_pe;
 #[cfg(all(target_arch = "x86", not(target_os = "windows")))]
 pub mod i686_elf;
+#[cfg(all(target_arch = "riscv64", target_os = "linux"))]
+pub mod riscv64_elf;
@@ -0,0 +1,165 @@
+// Relocation types
+const R_RISCV_NONE: u32 = 0; // none
+const R_RISCV_64: u32 = 2; // word64   S + A
+const R_RISCV_JUMP_SLOT: u32 = 5; // word64   S
+const R_RISCV_RELATIVE: u32 = 3; // word64   B + A
+const R_RISCV_IRELATIVE: u32 = 58; // word64   indirect (B + A)
+
+const DT_PLTRELSZ: u64 = 2;
+const DT_SYMTAB: u64 = 6;
+const DT_RELA: u64 = 7;
+const DT_RELASZ: u64 = 8;
+const DT_RELAENT: u64 = 9;
+const DT_JMPREL: u64 = 23;
+const DT_RELRSZ: u64 = 35;
+const DT_RELR: u64 = 36;
+
+#[repr(C, packed)]
+struct Elf64Dyn {
+    d_tag: u64,
+    d_val_or_ptr: u64,
+}
+
+#[repr(C, packed)]
+struct Elf64Rela {
+    r_offset: u64,
+    r_info: u64,
+    r_addend: u64,
+}
+
+#[allow(dead_code)]
+#[repr(C, packed)]
+struct Elf64Sym {
+    st_name: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+    st_value: u64,
+    st_size: u64,
+}
+
+unsafe fn locate_dynamic_hdr(addr_dynamic_section: u64, d_tag: u64) -> *const Elf64Dyn {
+    unsafe {
+        let mut ptr = addr_dynamic_section as *const Elf64Dyn;
+        loop {
+            if (*ptr).d_tag == 0 {
+                break core::ptr::null();
+            }
+            if (*ptr).d_tag == d_tag {
+                break ptr;
+            }
+            ptr = ptr.add(1);
+        }
+    }
+}
+
+/// Returns the value of the dynamic section entry `d_tag`, or 0 if it does not exist.
+unsafe fn dynamic_value(addr_dynamic_section: u64, d_tag: u64) -> u64 {
+    unsafe {
+        let ptr = locate_dynamic_hdr(addr_dynamic_section, d_tag);
+        if core::ptr::eq(ptr, core::ptr::null()) {
+            0
+        } else {
+            (*ptr).d_val_or_ptr
+        }
+    }
+}
+
+pub unsafe extern "C" fn relocate(addr_image_base: u64, addr_dynamic_section: u64) {
+    unsafe {
+        let symtab = dynamic_value(addr_dynamic_section, DT_SYMTAB);
+
+        /* Packed relative relocations (DT_RELR) are applied first,
+         * since the resolvers of R_RISCV_IRELATIVE may depend on them. */
+        let relr = dynamic_value(addr_dynamic_section, DT_RELR);
+        let relrsz = dynamic_value(addr_dynamic_section, DT_RELRSZ);
+        relocate_relr(addr_image_base, relr, relrsz);
+
+        let rela = dynamic_value(addr_dynamic_section, DT_RELA);
+        let relasz = dynamic_value(addr_dynamic_section, DT_RELASZ);
+        let relaent = dynamic_value(addr_dynamic_section, DT_RELAENT);
+        relocate_rela(addr_image_base, symtab, rela, relasz, relaent);
+
+        /* DT_JMPREL holds the relocations for PLT and IPLT (which contains R_RISCV_IRELATIVE
+         * in static-pie binaries). They always use the same format as DT_RELA on riscv64. */
+        let jmprel = dynamic_value(addr_dynamic_section, DT_JMPREL);
+        let pltrelsz = dynamic_value(addr_dynamic_section, DT_PLTRELSZ);
+        relocate_rela(
+            addr_image_base,
+            symtab,
+            jmprel,
+            pltrelsz,
+            core::mem::size_of::<Elf64Rela>() as u64,
+        );
+    }
+}
+
+/// Applies the packed relative relocations in DT_RELR.
+/// Each even entry is the offset of the next word to relocate, and each odd entry
+/// is a bitmap of the words to relocate among the 63 words that follow.
+unsafe fn relocate_relr(addr_image_base: u64, relr: u64, relrsz: u64) {
+    unsafe {
+        let mut ptr_target: *mut u64 = core::ptr::null_mut();
+        let mut relr_addr = addr_image_base + relr;
+        while relr_addr < addr_image_base + relr + relrsz {
+            let entry = *(relr_addr as *const u64);
+            if entry & 1 == 0 {
+                ptr_target = (addr_image_base + entry) as *mut u64;
+                *ptr_target += addr_image_base;
+                ptr_target = ptr_target.add(1);
+            } else {
+                let mut bitmap = entry >> 1;
+                let mut ptr = ptr_target;
+                while bitmap != 0 {
+                    if bitmap & 1 != 0 {
+                        *ptr += addr_image_base;
+                    }
+                    bitmap >>= 1;
+                    ptr = ptr.add(1);
+                }
+                ptr_target = ptr_target.add(63);
+            }
+            relr_addr += 8;
+        }
+    }
+}
+
+/// Applies the relocations in a DT_RELA or DT_JMPREL table.
+/// Symbols must be defined within the binary, since there is nothing to link against.
+unsafe fn relocate_rela(addr_image_base: u64, symtab: u64, rela: u64, relasz: u64, relaent: u64) {
+    unsafe {
+        let rela_base = (addr_image_base + rela) as usize;
+        let mut rela_addr = rela_base;
+        while rela_addr < rela_base + relasz as usize {
+            let rela_ptr = rela_addr as *const Elf64Rela;
+            let r_info = (*rela_ptr).r_info;
+            let r_info_type = r_info as u32;
+            let addr = (addr_image_base + (*rela_ptr).r_offset) as usize;
+            if r_info_type == R_RISCV_RELATIVE {
+                let value = addr_image_base.wrapping_add((*rela_ptr).r_addend);
+                core::ptr::write(addr as *mut u64, value);
+            } else if r_info_type == R_RISCV_64 || r_info_type == R_RISCV_JUMP_SLOT {
+                let sym_ptr = (addr_image_base + symtab) as *const Elf64Sym;
+                let sym_ptr = sym_ptr.add((r_info >> 32) as usize);
+                if (*sym_ptr).st_shndx == 0 {
+                    // Undefined symbol
+                    panic!();
+                }
+                let value =
+                    (addr_image_base + (*sym_ptr).st_value).wrapping_add((*rela_ptr).r_addend);
+                core::ptr::write(addr as *mut u64, value);
+            } else if r_info_type == R_RISCV_IRELATIVE {
+                let resolver: extern "C" fn() -> u64 = core::mem::transmute(
+                    addr_image_base.wrapping_add((*rela_ptr).r_addend) as usize,
+                );
+                core::ptr::write(addr as *mut u64, resolver());
+            } else if r_info_type == R_RISCV_NONE {
+                // Do nothing
+            } else {
+                // Unimplemented or unknown relocation type
+                panic!();
+            }
+            rela_addr += relaent as usize;
+        }
+    }
+}
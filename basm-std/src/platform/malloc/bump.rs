@@ -0,0 +1,277 @@
+//! A region-based bump allocator, enabled by the `bump-alloc` feature in place of dlmalloc.
+//!
+//! Memory is carved out of chunks obtained from the system allocator and is never returned.
+//! `dealloc` only reclaims the most recent allocation (i.e., a tail free), and `realloc` grows the
+//! most recent allocation in place when possible. This is fast and small, and suits solutions that
+//! build pointer-heavy structures once and never free them, but it can waste a lot of memory for
+//! solutions that repeatedly allocate and free (e.g., growing many `Vec`s in turn).
+use super::dlmalloc_interface::DlmallocAllocator;
+#[cfg(not(test))]
+use crate::platform::{Global, allocator};
+
+const MIN_CHUNK: usize = 1 << 20;
+const MAX_CHUNK: usize = 1 << 26;
+
+struct Bump {
+    top: usize,
+    end: usize,
+    /// Memory from `clean` to `end` has never been handed out, and hence is still zeroed
+    /// if the system allocator provides zeroed memory.
+    clean: usize,
+    next_chunk: usize,
+}
+
+#[cfg(not(test))]
+static BUMP: Global<Bump> = Global::new(Bump::new());
+
+impl Bump {
+    const fn new() -> Self {
+        Self {
+            top: 0,
+            end: 0,
+            clean: 0,
+            next_chunk: MIN_CHUNK,
+        }
+    }
+    /// Obtains a new chunk of at least `min_size` bytes. Returns `false` if the system is out of memory.
+    fn grow<A: DlmallocAllocator + Default>(&mut self, min_size: usize) -> bool {
+        let system = A::default();
+        let size = min_size
+            .max(self.next_chunk)
+            .next_multiple_of(system.page_size());
+        let (base, size, _) = system.alloc(size);
+        if base.is_null() {
+            return false;
+        }
+        if base as usize == self.end {
+            /* the new chunk is contiguous to the current one */
+            self.end += size;
+        } else {
+            self.top = base as usize;
+            self.end = base as usize + size;
+            self.clean = base as usize;
+        }
+        self.next_chunk = (self.next_chunk * 2).min(MAX_CHUNK);
+        true
+    }
+    /// Returns the allocated address (or 0 on failure) and the `clean` mark before the allocation.
+    #[inline(always)]
+    fn bump<A: DlmallocAllocator + Default>(
+        &mut self,
+        size: usize,
+        align: usize,
+    ) -> (usize, usize) {
+        let mut ptr = self.top.next_multiple_of(align);
+        if ptr > self.end || size > self.end - ptr {
+            if !self.grow::<A>(size + align) {
+                return (0, 0);
+            }
+            ptr = self.top.next_multiple_of(align);
+        }
+        let clean = self.clean;
+        self.top = ptr + size;
+        self.clean = self.clean.max(self.top);
+        (ptr, clean)
+    }
+    #[inline(always)]
+    fn alloc<A: DlmallocAllocator + Default>(&mut self, size: usize, align: usize) -> *mut u8 {
+        self.bump::<A>(size, align).0 as *mut u8
+    }
+    unsafe fn alloc_zeroed<A: DlmallocAllocator + Default>(
+        &mut self,
+        size: usize,
+        align: usize,
+    ) -> *mut u8 {
+        let (ptr, clean) = self.bump::<A>(size, align);
+        if ptr != 0 {
+            let dirty = if A::default().allocates_zeros() {
+                clean.clamp(ptr, ptr + size) - ptr
+            } else {
+                size
+            };
+            unsafe { core::ptr::write_bytes(ptr as *mut u8, 0, dirty) };
+        }
+        ptr as *mut u8
+    }
+    fn dealloc(&mut self, ptr: *mut u8, size: usize) {
+        if ptr as usize + size == self.top {
+            self.top = ptr as usize;
+        }
+    }
+    unsafe fn realloc<A: DlmallocAllocator + Default>(
+        &mut self,
+        ptr: *mut u8,
+        old_size: usize,
+        old_align: usize,
+        new_size: usize,
+    ) -> *mut u8 {
+        if ptr as usize + old_size == self.top && new_size <= self.end - ptr as usize {
+            /* the most recent allocation can be resized in place */
+            self.top = ptr as usize + new_size;
+            self.clean = self.clean.max(self.top);
+            return ptr;
+        }
+        if new_size <= old_size {
+            return ptr;
+        }
+        let ptr_new = self.alloc::<A>(new_size, old_align);
+        if !ptr_new.is_null() {
+            unsafe { core::ptr::copy_nonoverlapping(ptr, ptr_new, old_size) };
+        }
+        ptr_new
+    }
+}
+
+#[cfg(not(test))]
+unsafe fn bump_alloc<A: DlmallocAllocator + Default>(size: usize, align: usize) -> *mut u8 {
+    BUMP.with(|bump| bump.alloc::<A>(size, align))
+}
+#[cfg(not(test))]
+unsafe fn bump_alloc_zeroed<A: DlmallocAllocator + Default>(size: usize, align: usize) -> *mut u8 {
+    BUMP.with(|bump| unsafe { bump.alloc_zeroed::<A>(size, align) })
+}
+#[cfg(not(test))]
+unsafe fn bump_dealloc(ptr: *mut u8, size: usize, _align: usize) {
+    BUMP.with(|bump| bump.dealloc(ptr, size))
+}
+#[cfg(not(test))]
+unsafe fn bump_realloc<A: DlmallocAllocator + Default>(
+    ptr: *mut u8,
+    old_size: usize,
+    old_align: usize,
+    new_size: usize,
+) -> *mut u8 {
+    BUMP.with(|bump| unsafe { bump.realloc::<A>(ptr, old_size, old_align, new_size) })
+}
+
+/// Installs the bump allocator, which obtains its chunks from the system allocator `A`.
+///
+/// # Safety
+/// This must be called before any allocation is made, since memory allocated by
+/// the previously installed allocator cannot be freed by the bump allocator.
+#[cfg(not(test))]
+pub unsafe fn install<A: DlmallocAllocator + Default>() {
+    unsafe {
+        allocator::install_malloc_impl(
+            bump_alloc::<A>,
+            bump_alloc_zeroed::<A>,
+            bump_dealloc,
+            bump_realloc::<A>,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::alloc::{Layout, alloc};
+
+    const PAGE: usize = 4096;
+
+    /// Hands out pages filled with 0xAA while claiming that they are zeroed,
+    /// so that the tests can tell which bytes have been zeroed by the bump allocator.
+    #[derive(Default)]
+    struct FakeSystem;
+
+    unsafe impl DlmallocAllocator for FakeSystem {
+        fn alloc(&self, size: usize) -> (*mut u8, usize, u32) {
+            let ptr = unsafe { alloc(Layout::from_size_align(size, PAGE).unwrap()) };
+            unsafe { ptr.write_bytes(0xaa, size) };
+            (ptr, size, 0)
+        }
+        fn remap(
+            &self,
+            _ptr: *mut u8,
+            _oldsize: usize,
+            _newsize: usize,
+            _can_move: bool,
+        ) -> *mut u8 {
+            core::ptr::null_mut()
+        }
+        fn free_part(&self, _ptr: *mut u8, _oldsize: usize, _newsize: usize) -> bool {
+            false
+        }
+        fn free(&self, _ptr: *mut u8, _size: usize) -> bool {
+            false
+        }
+        fn can_release_part(&self, _flags: u32) -> bool {
+            false
+        }
+        fn allocates_zeros(&self) -> bool {
+            true
+        }
+        fn page_size(&self) -> usize {
+            PAGE
+        }
+    }
+
+    #[test]
+    fn bump_tail_free() {
+        let mut bump = Bump::new();
+        let a = bump.alloc::<FakeSystem>(100, 8);
+        let b = bump.alloc::<FakeSystem>(50, 16);
+        assert_eq!(b as usize % 16, 0);
+        assert!(b as usize >= a as usize + 100);
+        // Only the most recent allocation is reclaimed
+        bump.dealloc(a, 100);
+        assert_eq!(bump.top, b as usize + 50);
+        bump.dealloc(b, 50);
+        assert_eq!(bump.top, b as usize);
+        assert_eq!(bump.alloc::<FakeSystem>(50, 16), b);
+    }
+
+    #[test]
+    fn bump_realloc_in_place() {
+        let mut bump = Bump::new();
+        unsafe {
+            let p = bump.alloc::<FakeSystem>(100, 16);
+            p.write_bytes(7, 100);
+            assert_eq!(bump.realloc::<FakeSystem>(p, 100, 16, 1000), p);
+            assert_eq!(bump.top, p as usize + 1000);
+            assert_eq!(bump.realloc::<FakeSystem>(p, 1000, 16, 10), p);
+            assert_eq!(bump.top, p as usize + 10);
+
+            // Not the most recent allocation anymore, so it is moved when growing
+            let q = bump.alloc::<FakeSystem>(8, 8);
+            assert_eq!(bump.realloc::<FakeSystem>(p, 10, 16, 5), p);
+            let r = bump.realloc::<FakeSystem>(p, 10, 16, 20);
+            assert!(r as usize > q as usize);
+            assert_eq!(r as usize % 16, 0);
+            assert_eq!(core::slice::from_raw_parts(r, 10), [7; 10]);
+
+            // Growing beyond the chunk moves it to a new chunk
+            let end = bump.end;
+            let s = bump.realloc::<FakeSystem>(r, 20, 16, end - r as usize + 1);
+            assert_ne!(s, r);
+            assert_eq!(core::slice::from_raw_parts(s, 10), [7; 10]);
+        }
+    }
+
+    #[test]
+    fn bump_zeroes_only_dirty_memory() {
+        let mut bump = Bump::new();
+        unsafe {
+            // Fresh memory is not written, since the system provides zeroed memory
+            let p = bump.alloc_zeroed::<FakeSystem>(64, 8);
+            assert_eq!(core::slice::from_raw_parts(p, 64), [0xaa; 64]);
+            // Memory that was handed out before is zeroed
+            p.write_bytes(1, 64);
+            bump.dealloc(p, 64);
+            let q = bump.alloc_zeroed::<FakeSystem>(128, 8);
+            assert_eq!(q, p);
+            assert_eq!(core::slice::from_raw_parts(q, 64), [0; 64]);
+            assert_eq!(core::slice::from_raw_parts(q.add(64), 64), [0xaa; 64]);
+            assert_eq!(bump.clean, q as usize + 128);
+        }
+    }
+
+    #[test]
+    fn bump_grows_chunks() {
+        let mut bump = Bump::new();
+        let big = 3 * MIN_CHUNK;
+        let p = bump.alloc::<FakeSystem>(big, PAGE);
+        assert!(!p.is_null());
+        assert!(bump.end - p as usize >= big);
+        assert_eq!(bump.next_chunk, 2 * MIN_CHUNK);
+    }
+}
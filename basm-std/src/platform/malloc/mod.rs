@@ -1,10 +1,18 @@
+#[cfg(any(feature = "bump-alloc", test))]
+pub mod bump;
+#[cfg(not(test))]
 pub mod dlmalloc;
 pub mod dlmalloc_interface;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(not(any(test, target_arch = "wasm32")))]
 pub mod dlmalloc_linux;
-#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+#[cfg(all(not(test), target_os = "macos", target_arch = "aarch64"))]
 pub mod dlmalloc_macos;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(not(test), target_arch = "wasm32"))]
 pub mod dlmalloc_wasm32;
-#[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+#[cfg(not(any(
+    test,
+    target_arch = "wasm32",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
 pub mod dlmalloc_windows;
@@ -2,15 +2,27 @@
 pub mod allocator;
 #[cfg(not(test))]
 pub mod codegen;
+mod global;
+pub use global::Global;
 pub mod io;
 #[cfg(not(test))]
 pub mod loader;
-#[cfg(not(test))]
+// In tests, only the parts that do not depend on the platform are built
 pub mod malloc;
 #[cfg(not(test))]
 pub mod os;
 #[cfg_attr(test, path = "services_std.rs")]
 pub mod services;
+#[cfg(test)]
+pub mod testing;
+pub mod time;
+#[cfg(all(
+    not(test),
+    target_os = "linux",
+    feature = "tls",
+    not(feature = "short")
+))]
+pub mod tls;
 
 #[cfg(not(test))]
 pub fn init(platform_data_by_loader: usize) {
@@ -19,7 +31,11 @@ pub fn init(platform_data_by_loader: usize) {
     let pd = services::platform_data();
     unsafe {
         match pd.env_id {
-            #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+            #[cfg(not(any(
+                target_arch = "wasm32",
+                target_arch = "aarch64",
+                target_arch = "riscv64"
+            )))]
             #[cfg(not(feature = "short"))]
             services::ENV_ID_WINDOWS => {
                 /* use OS APIs directly */
@@ -47,10 +63,14 @@ pub fn init(platform_data_by_loader: usize) {
                 unreachable!();
             }
         }
+        #[cfg(all(target_os = "linux", feature = "tls", not(feature = "short")))]
+        tls::init();
     }
 }
 #[cfg(not(test))]
 pub fn try_exit() {
+    #[cfg(all(feature = "alloc-stats", not(feature = "submit")))]
+    allocator::stats::report();
     #[cfg(not(target_arch = "wasm32"))]
     {
         let pd = services::platform_data();
@@ -1,10 +1,13 @@
+#[cfg(not(feature = "bump-alloc"))]
 use super::super::allocator;
-use super::super::malloc::{dlmalloc, dlmalloc_linux};
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::malloc::dlmalloc;
+use super::super::malloc::dlmalloc_linux;
 
 pub mod syscall {
     #[cfg(not(target_arch = "x86"))]
     use core::arch::asm;
-    #[cfg(target_arch = "x86")]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     use core::arch::naked_asm;
 
     pub const PROT_NONE: i32 = 0x00;
@@ -17,11 +20,29 @@ pub mod syscall {
     pub const MREMAP_MAYMOVE: i32 = 0x01;
     pub const MAP_FAILED: *mut u8 = usize::MAX as *mut u8;
     pub const RLIMIT_STACK: usize = 3;
+    pub const CLOCK_MONOTONIC: usize = 1;
+    pub const GRND_NONBLOCK: usize = 0x01;
+    pub const S_IFMT: u32 = 0o170000;
+    pub const S_IFREG: u32 = 0o100000;
+    pub const O_RDONLY: usize = 0o0;
+    pub const O_WRONLY: usize = 0o1;
+    pub const O_CREAT: usize = 0o100;
+    pub const O_TRUNC: usize = 0o1000;
+    pub const SIGSEGV: usize = 11;
+    pub const SA_SIGINFO: usize = 0x04;
+    pub const SA_ONSTACK: usize = 0x08000000;
+    pub const SA_RESTORER: usize = 0x04000000;
+    #[cfg(target_arch = "x86_64")]
+    pub const ARCH_SET_FS: usize = 0x1002;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const AT_FDCWD: isize = -100;
 
     #[cfg(target_arch = "x86_64")]
     mod id_list {
         pub const READ: usize = 0;
         pub const WRITE: usize = 1;
+        pub const OPEN: usize = 2;
+        pub const CLOSE: usize = 3;
         pub const FSTAT: usize = 5;
         pub const MMAP: usize = 9;
         pub const MREMAP: usize = 25;
@@ -29,30 +50,49 @@ pub mod syscall {
         pub const EXIT_GROUP: usize = 231;
         pub const GETRLIMIT: usize = 97;
         pub const SETRLIMIT: usize = 160;
+        pub const CLOCK_GETTIME: usize = 228;
+        pub const GETRANDOM: usize = 318;
+        pub const RT_SIGACTION: usize = 13;
+        pub const RT_SIGRETURN: usize = 15;
+        pub const SIGALTSTACK: usize = 131;
+        pub const ARCH_PRCTL: usize = 158;
     }
     #[cfg(target_arch = "x86")]
     mod id_list {
         pub const READ: usize = 3;
         pub const WRITE: usize = 4;
-        //pub const FSTAT: usize = 108;
+        pub const OPEN: usize = 5;
+        pub const CLOSE: usize = 6;
+        pub const FSTAT: usize = 108;
         pub const MMAP: usize = 90;
         pub const MREMAP: usize = 163;
         pub const MUNMAP: usize = 91;
         pub const EXIT_GROUP: usize = 252;
         pub const GETRLIMIT: usize = 76;
         pub const SETRLIMIT: usize = 75;
+        pub const CLOCK_GETTIME: usize = 265;
+        pub const GETRANDOM: usize = 355;
+        pub const RT_SIGACTION: usize = 174;
+        pub const SIGALTSTACK: usize = 186;
+        pub const SET_THREAD_AREA: usize = 243;
     }
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
     mod id_list {
         pub const READ: usize = 63;
         pub const WRITE: usize = 64;
-        //pub const FSTAT: usize = 80;
+        pub const OPENAT: usize = 56;
+        pub const CLOSE: usize = 57;
+        pub const FSTAT: usize = 80;
         pub const MMAP: usize = 222;
         pub const MREMAP: usize = 216;
         pub const MUNMAP: usize = 215;
         pub const EXIT_GROUP: usize = 94;
         pub const GETRLIMIT: usize = 163;
         pub const SETRLIMIT: usize = 164;
+        pub const CLOCK_GETTIME: usize = 113;
+        pub const GETRANDOM: usize = 278;
+        pub const RT_SIGACTION: usize = 134;
+        pub const SIGALTSTACK: usize = 132;
     }
 
     #[derive(Default)]
@@ -62,6 +102,54 @@ pub mod syscall {
         pub rlim_max: usize,
     }
 
+    #[derive(Default)]
+    #[repr(C)]
+    pub struct Timespec {
+        pub tv_sec: isize,
+        pub tv_nsec: isize,
+    }
+
+    /// The kernel's `struct sigaction` (not glibc's), as taken by `rt_sigaction`.
+    /// riscv64 has no `sa_restorer`, since the kernel always provides the signal trampoline there.
+    #[derive(Default)]
+    #[repr(C)]
+    pub struct SigAction {
+        pub sa_handler: usize,
+        pub sa_flags: usize,
+        #[cfg(not(target_arch = "riscv64"))]
+        pub sa_restorer: usize,
+        pub sa_mask: u64,
+    }
+
+    #[derive(Default)]
+    #[repr(C)]
+    pub struct SigAltStack {
+        pub ss_sp: usize,
+        pub ss_flags: i32,
+        pub ss_size: usize,
+    }
+
+    /// The leading fields of `siginfo_t`, as laid out for `SIGSEGV`.
+    #[repr(C)]
+    pub struct SigInfo {
+        pub si_signo: i32,
+        pub si_errno: i32,
+        pub si_code: i32,
+        pub si_addr: usize,
+    }
+
+    /// A segment descriptor for `set_thread_area`. `flags` holds the bitfields following `limit`
+    /// (`seg_32bit`, `contents`, `read_exec_only`, `limit_in_pages`, `seg_not_present`, `useable`).
+    #[cfg(target_arch = "x86")]
+    #[derive(Default)]
+    #[repr(C)]
+    pub struct UserDesc {
+        pub entry_number: u32,
+        pub base_addr: u32,
+        pub limit: u32,
+        pub flags: u32,
+    }
+
     #[cfg(target_arch = "x86_64")]
     #[derive(Default)]
     #[repr(C, packed)]
@@ -86,6 +174,54 @@ pub mod syscall {
         pad5: [u8; 32],
     }
 
+    #[cfg(target_arch = "x86")]
+    #[derive(Default)]
+    #[repr(C, packed)]
+    pub struct Stat {
+        pub st_dev: u32,
+        pub st_ino: u32,
+        pub st_mode: u16,
+        pub st_nlink: u16,
+        pub st_uid: u16,
+        pub st_gid: u16,
+        pub st_rdev: u32,
+        pub st_size: u32,
+        pub st_blksize: u32,
+        pub st_blocks: u32,
+        pub st_atime: u32,
+        pad1: u32,
+        pub st_mtime: u32,
+        pad2: u32,
+        pub st_ctime: u32,
+        pad3: u32,
+        pad4: [u32; 2],
+    }
+
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    #[derive(Default)]
+    #[repr(C, packed)]
+    pub struct Stat {
+        pub st_dev: u64,
+        pub st_ino: u64,
+        pub st_mode: u32,
+        pub st_nlink: u32,
+        pub st_uid: u32,
+        pub st_gid: u32,
+        pub st_rdev: u64,
+        pad1: u64,
+        pub st_size: i64,
+        pub st_blksize: i32,
+        pad2: i32,
+        pub st_blocks: i64,
+        pub st_atime: i64,
+        pad3: u64,
+        pub st_mtime: i64,
+        pad4: u64,
+        pub st_ctime: i64,
+        pad5: u64,
+        pad6: [u32; 2],
+    }
+
     #[cfg(target_arch = "x86_64")]
     #[inline(always)]
     pub unsafe fn syscall1(call_id: usize, arg0: usize) -> usize {
@@ -214,6 +350,39 @@ pub mod syscall {
             out
         }
     }
+    #[cfg(target_arch = "riscv64")]
+    pub unsafe fn syscall(
+        call_id: usize,
+        arg0: usize,
+        arg1: usize,
+        arg2: usize,
+        arg3: usize,
+        arg4: usize,
+        arg5: usize,
+    ) -> usize {
+        unsafe {
+            let out;
+            asm!(
+                "ecall",
+                in("a7") call_id,
+                in("a0") arg0,
+                in("a1") arg1,
+                in("a2") arg2,
+                in("a3") arg3,
+                in("a4") arg4,
+                in("a5") arg5,
+                lateout("a0") out
+            );
+            out
+        }
+    }
+
+    /// Returns whether an address returned by a syscall (e.g., `mmap`) is a negated error number.
+    /// Only the last page can hold one, since valid addresses may exceed `isize::MAX` on 32-bit targets.
+    #[inline(always)]
+    pub fn is_err_ptr(ptr: *mut u8) -> bool {
+        ptr as usize > usize::MAX - 4095
+    }
 
     #[cfg(target_arch = "x86_64")]
     #[inline(always)]
@@ -267,7 +436,7 @@ pub mod syscall {
             ) as *mut u8
         }
     }
-    #[cfg(target_arch = "aarch64")]
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
     #[inline(always)]
     pub unsafe fn mmap(
         addr: *const u8,
@@ -320,6 +489,35 @@ pub mod syscall {
     pub unsafe fn write(fd: usize, buf: *const u8, count: usize) -> usize {
         unsafe { syscall3(id_list::WRITE, fd, buf as usize, count) }
     }
+    /// Opens the file at `path`, which must be a null-terminated string.
+    /// Returns the file descriptor, or a negated error number on failure.
+    #[cfg(not(any(target_arch = "aarch64", target_arch = "riscv64")))]
+    #[inline(always)]
+    pub unsafe fn open(path: *const u8, flags: usize, mode: usize) -> usize {
+        unsafe { syscall3(id_list::OPEN, path as usize, flags, mode) }
+    }
+    /// Opens the file at `path`, which must be a null-terminated string.
+    /// Returns the file descriptor, or a negated error number on failure.
+    /// (aarch64 and riscv64 only provide `openat`, so this is relative to the current directory.)
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    #[inline(always)]
+    pub unsafe fn open(path: *const u8, flags: usize, mode: usize) -> usize {
+        unsafe {
+            syscall(
+                id_list::OPENAT,
+                AT_FDCWD as usize,
+                path as usize,
+                flags,
+                mode,
+                0,
+                0,
+            )
+        }
+    }
+    #[inline(always)]
+    pub unsafe fn close(fd: usize) -> usize {
+        unsafe { syscall1(id_list::CLOSE, fd) }
+    }
     #[inline(always)]
     pub unsafe fn exit_group(status: usize) -> ! {
         unsafe {
@@ -355,20 +553,86 @@ pub mod syscall {
             )
         }
     }
+    #[inline(always)]
+    pub unsafe fn clock_gettime(clk_id: usize, tp: &mut Timespec) -> usize {
+        unsafe {
+            syscall3(
+                id_list::CLOCK_GETTIME,
+                clk_id,
+                tp as *mut Timespec as usize,
+                0,
+            )
+        }
+    }
+    #[inline(always)]
+    pub unsafe fn getrandom(buf: *mut u8, buflen: usize, flags: usize) -> usize {
+        unsafe { syscall3(id_list::GETRANDOM, buf as usize, buflen, flags) }
+    }
+    #[inline(always)]
+    pub unsafe fn rt_sigaction(sig: usize, act: &SigAction, oldact: *mut SigAction) -> usize {
+        unsafe {
+            syscall(
+                id_list::RT_SIGACTION,
+                sig,
+                act as *const SigAction as usize,
+                oldact as usize,
+                core::mem::size_of::<u64>(),
+                0,
+                0,
+            )
+        }
+    }
+    /// The signal trampoline to be passed as `sa_restorer`, which is mandatory on x86_64.
+    #[cfg(target_arch = "x86_64")]
+    #[unsafe(naked)]
+    pub unsafe extern "C" fn rt_sigreturn() {
+        naked_asm!("mov eax, {}", "syscall", const id_list::RT_SIGRETURN)
+    }
+    #[inline(always)]
+    pub unsafe fn sigaltstack(ss: &SigAltStack, old_ss: *mut SigAltStack) -> usize {
+        unsafe {
+            syscall3(
+                id_list::SIGALTSTACK,
+                ss as *const SigAltStack as usize,
+                old_ss as usize,
+                0,
+            )
+        }
+    }
     #[cfg(target_arch = "x86_64")]
+    #[inline(always)]
+    pub unsafe fn arch_prctl(code: usize, addr: usize) -> usize {
+        unsafe { syscall3(id_list::ARCH_PRCTL, code, addr, 0) }
+    }
+    #[cfg(target_arch = "x86")]
+    #[inline(always)]
+    pub unsafe fn set_thread_area(u_info: &mut UserDesc) -> usize {
+        unsafe {
+            syscall3(
+                id_list::SET_THREAD_AREA,
+                u_info as *mut UserDesc as usize,
+                0,
+                0,
+            )
+        }
+    }
+    #[inline(always)]
     pub unsafe fn fstat(fd: usize, st: &mut Stat) -> usize {
         unsafe { syscall3(id_list::FSTAT, fd, st as *mut Stat as usize, 0) }
     }
 }
 
+#[cfg(not(feature = "bump-alloc"))]
 static mut DLMALLOC: dlmalloc::Dlmalloc<dlmalloc_linux::System> =
     dlmalloc::Dlmalloc::new(dlmalloc_linux::System::new());
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.memalign(align, size)
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
@@ -379,12 +643,14 @@ unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
         ptr
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_dealloc(ptr: *mut u8, _size: usize, _align: usize) {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.free(ptr);
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_realloc(
     ptr: *mut u8,
     old_size: usize,
@@ -431,6 +697,90 @@ mod services_override {
     }
 }
 
+/// Reports segmentation faults to stderr in local runs, since the process would otherwise die silently.
+/// A fault just below the stack limit is reported as a stack overflow.
+#[cfg(not(any(feature = "short", feature = "submit")))]
+mod segv_handler {
+    use super::syscall;
+    use crate::platform::Global;
+
+    /// The size of the gap kept below the stack (`stack_guard_gap`), in which a fault is also regarded as a stack overflow.
+    const STACK_GUARD_GAP: usize = 1 << 20;
+    static ALT_STACK: Global<[u8; 1 << 16]> = Global::new([0; 1 << 16]);
+    /// The top of the stack and its limit.
+    static STACK: Global<(usize, usize)> = Global::new((0, 0));
+
+    /// Installs the handler. This must be called from near the top of the stack (i.e., during initialization),
+    /// after the stack limit is raised.
+    pub unsafe fn install() {
+        unsafe {
+            let top = 0u8;
+            let mut rlim: syscall::RLimit = Default::default();
+            if syscall::getrlimit(syscall::RLIMIT_STACK, &mut rlim) == 0 {
+                STACK.set((&top as *const u8 as usize, rlim.rlim_cur));
+            }
+
+            /* The handler must run on an alternate stack, since the stack is exhausted on stack overflow */
+            let ss = ALT_STACK.with(|stack| syscall::SigAltStack {
+                ss_sp: stack.as_mut_ptr() as usize,
+                ss_flags: 0,
+                ss_size: stack.len(),
+            });
+            syscall::sigaltstack(&ss, core::ptr::null_mut());
+
+            #[cfg(target_arch = "x86_64")]
+            let act = syscall::SigAction {
+                sa_handler: handler as *const () as usize,
+                sa_flags: syscall::SA_SIGINFO | syscall::SA_ONSTACK | syscall::SA_RESTORER,
+                sa_restorer: syscall::rt_sigreturn as *const () as usize,
+                ..Default::default()
+            };
+            #[cfg(not(target_arch = "x86_64"))]
+            let act = syscall::SigAction {
+                sa_handler: handler as *const () as usize,
+                sa_flags: syscall::SA_SIGINFO | syscall::SA_ONSTACK,
+                ..Default::default()
+            };
+            syscall::rt_sigaction(syscall::SIGSEGV, &act, core::ptr::null_mut());
+        }
+    }
+
+    fn is_stack_overflow(addr: usize) -> bool {
+        let (top, limit) = STACK.get();
+        /* An unlimited stack (`RLIM_INFINITY`) has no guard to hit */
+        match limit.checked_add(STACK_GUARD_GAP) {
+            Some(size) if size < top => addr <= top && addr >= top - size,
+            _ => false,
+        }
+    }
+
+    unsafe extern "C" fn handler(_sig: i32, info: *const syscall::SigInfo, _ctx: *mut u8) {
+        unsafe {
+            let addr = (*info).si_addr;
+            if is_stack_overflow(addr) {
+                write_stderr(b"stack overflow\n");
+            } else {
+                let mut buf = *b"segmentation fault at 0x0000000000000000\n";
+                let digits = 2 * core::mem::size_of::<usize>();
+                let prefix = buf.len() - 17;
+                for i in 0..digits {
+                    buf[prefix + i] = b"0123456789abcdef"[(addr >> (4 * (digits - 1 - i))) & 15];
+                }
+                buf[prefix + digits] = b'\n';
+                write_stderr(&buf[..prefix + digits + 1]);
+            }
+            // Follow the exit code that shells report for a process killed by SIGSEGV (128 + 11).
+            syscall::exit_group(128 + syscall::SIGSEGV)
+        }
+    }
+
+    unsafe fn write_stderr(buf: &[u8]) {
+        unsafe {
+            syscall::write(2, buf.as_ptr(), buf.len());
+        }
+    }
+}
+
 pub unsafe fn init() {
     unsafe {
         /* Ensure stack size is at least 256 MiB, when running locally
@@ -452,15 +802,20 @@ pub unsafe fn init() {
                     rlim.rlim_cur = 256 * 1024 * 1024;
                     syscall::setrlimit(syscall::RLIMIT_STACK, &rlim);
                 }
+                #[cfg(not(feature = "submit"))]
+                segv_handler::install();
             }
         }
 
+        #[cfg(not(feature = "bump-alloc"))]
         allocator::install_malloc_impl(
             dlmalloc_alloc,
             dlmalloc_alloc_zeroed,
             dlmalloc_dealloc,
             dlmalloc_realloc,
         );
+        #[cfg(feature = "bump-alloc")]
+        super::super::malloc::bump::install::<dlmalloc_linux::System>();
 
         /* "short" on "Linux" will use syscalls directly to reduce code size */
         #[cfg(not(all(feature = "short", target_os = "linux")))]
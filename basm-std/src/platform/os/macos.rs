@@ -1,5 +1,9 @@
-use super::super::malloc::{dlmalloc, dlmalloc_macos};
-use super::super::{allocator, services};
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::allocator;
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::malloc::dlmalloc;
+use super::super::malloc::dlmalloc_macos;
+use super::super::services;
 
 pub mod syscall {
     pub const PROT_READ: i32 = 0x01;
@@ -37,14 +41,17 @@ pub mod syscall {
     }
 }
 
+#[cfg(not(feature = "bump-alloc"))]
 static mut DLMALLOC: dlmalloc::Dlmalloc<dlmalloc_macos::System> =
     dlmalloc::Dlmalloc::new(dlmalloc_macos::System::new());
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.memalign(align, size)
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
@@ -55,12 +62,14 @@ unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
         ptr
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_dealloc(ptr: *mut u8, _size: usize, _align: usize) {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.free(ptr);
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_realloc(
     ptr: *mut u8,
     old_size: usize,
@@ -113,12 +122,15 @@ pub unsafe fn init() {
             }
         }
 
+        #[cfg(not(feature = "bump-alloc"))]
         allocator::install_malloc_impl(
             dlmalloc_alloc,
             dlmalloc_alloc_zeroed,
             dlmalloc_dealloc,
             dlmalloc_realloc,
         );
+        #[cfg(feature = "bump-alloc")]
+        super::super::malloc::bump::install::<dlmalloc_macos::System>();
 
         services::install_single_service(
             5,
@@ -5,5 +5,9 @@ pub mod macos;
 pub mod unknown;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm32;
-#[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+#[cfg(not(any(
+    target_arch = "wasm32",
+    target_arch = "aarch64",
+    target_arch = "riscv64"
+)))]
 pub mod windows;
@@ -1,14 +1,21 @@
-use super::super::malloc::{dlmalloc, dlmalloc_wasm32};
-use super::super::{allocator, services};
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::allocator;
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::malloc::dlmalloc;
+use super::super::malloc::dlmalloc_wasm32;
+use super::super::services;
 
+#[cfg(not(feature = "bump-alloc"))]
 static mut DLMALLOC: dlmalloc::Dlmalloc<dlmalloc_wasm32::System> =
     dlmalloc::Dlmalloc::new(dlmalloc_wasm32::System::new());
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.memalign(align, size)
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
@@ -19,12 +26,14 @@ unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
         ptr
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_dealloc(ptr: *mut u8, _size: usize, _align: usize) {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.free(ptr);
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_realloc(
     ptr: *mut u8,
     old_size: usize,
@@ -50,16 +59,25 @@ unsafe fn dlmalloc_realloc(
 unsafe extern "C" {
     fn svc_read_stdio(fd: usize, buf: *mut u8, count: usize) -> usize;
     fn svc_write_stdio(fd: usize, buf: *const u8, count: usize) -> usize;
+    fn svc_time_now() -> f64;
+}
+
+/// Returns `performance.now()` of the host, in milliseconds.
+pub fn time_now() -> f64 {
+    unsafe { svc_time_now() }
 }
 
 pub unsafe fn init() {
     unsafe {
+        #[cfg(not(feature = "bump-alloc"))]
         allocator::install_malloc_impl(
             dlmalloc_alloc,
             dlmalloc_alloc_zeroed,
             dlmalloc_dealloc,
             dlmalloc_realloc,
         );
+        #[cfg(feature = "bump-alloc")]
+        super::super::malloc::bump::install::<dlmalloc_wasm32::System>();
         services::install_single_service(5, svc_read_stdio as *const () as usize);
         services::install_single_service(6, svc_write_stdio as *const () as usize);
     }
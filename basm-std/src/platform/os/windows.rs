@@ -1,8 +1,12 @@
 #![allow(non_snake_case)]
 #![allow(clippy::missing_transmute_annotations)]
 
-use super::super::malloc::{dlmalloc, dlmalloc_windows};
-use super::super::{allocator, services};
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::allocator;
+#[cfg(not(feature = "bump-alloc"))]
+use super::super::malloc::dlmalloc;
+use super::super::malloc::dlmalloc_windows;
+use super::super::services;
 
 #[cfg(target_arch = "x86_64")]
 macro_rules! ms_abi {
@@ -63,6 +67,8 @@ pub struct WinApi {
     ptr_WriteFile: Option<ms_abi! {fn(usize, *const u8, u32, *mut u32, *mut Overlapped) -> i32}>,
     ptr_GetOverlappedResult: Option<ms_abi! {fn(usize, *mut Overlapped, *mut u32, i32) -> i32}>,
     ptr_GetLastError: Option<ms_abi! {fn() -> u32}>,
+    ptr_QueryPerformanceCounter: Option<ms_abi! {fn(*mut i64) -> i32}>,
+    ptr_QueryPerformanceFrequency: Option<ms_abi! {fn(*mut i64) -> i32}>,
     io_off: [u64; 3],
 }
 impl WinApi {
@@ -154,6 +160,14 @@ impl WinApi {
     pub unsafe fn GetLastError(&self) -> u32 {
         (self.ptr_GetLastError.unwrap())()
     }
+    #[inline(always)]
+    pub unsafe fn QueryPerformanceCounter(&self, lpPerformanceCount: *mut i64) -> i32 {
+        (self.ptr_QueryPerformanceCounter.unwrap())(lpPerformanceCount)
+    }
+    #[inline(always)]
+    pub unsafe fn QueryPerformanceFrequency(&self, lpFrequency: *mut i64) -> i32 {
+        (self.ptr_QueryPerformanceFrequency.unwrap())(lpFrequency)
+    }
 }
 pub static mut WINAPI: WinApi = WinApi {
     ptr_VirtualAlloc: None,
@@ -163,17 +177,22 @@ pub static mut WINAPI: WinApi = WinApi {
     ptr_WriteFile: None,
     ptr_GetOverlappedResult: None,
     ptr_GetLastError: None,
+    ptr_QueryPerformanceCounter: None,
+    ptr_QueryPerformanceFrequency: None,
     io_off: [0; 3],
 };
 
+#[cfg(not(feature = "bump-alloc"))]
 static mut DLMALLOC: dlmalloc::Dlmalloc<dlmalloc_windows::System> =
     dlmalloc::Dlmalloc::new(dlmalloc_windows::System::new());
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.memalign(align, size)
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
@@ -184,12 +203,14 @@ unsafe fn dlmalloc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
         ptr
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_dealloc(ptr: *mut u8, _size: usize, _align: usize) {
     unsafe {
         let dlmalloc = &mut *core::ptr::addr_of_mut!(DLMALLOC);
         dlmalloc.free(ptr);
     }
 }
+#[cfg(not(feature = "bump-alloc"))]
 unsafe fn dlmalloc_realloc(
     ptr: *mut u8,
     old_size: usize,
@@ -297,6 +318,14 @@ pub unsafe fn init() {
             kernel32,
             c"GetLastError".as_ptr(),
         )));
+        WINAPI.ptr_QueryPerformanceCounter = Some(core::mem::transmute(GetProcAddress(
+            kernel32,
+            c"QueryPerformanceCounter".as_ptr(),
+        )));
+        WINAPI.ptr_QueryPerformanceFrequency = Some(core::mem::transmute(GetProcAddress(
+            kernel32,
+            c"QueryPerformanceFrequency".as_ptr(),
+        )));
 
         // On Windows, set console codepage to UTF-8,
         // since the default encoding is (historically) MBCS
@@ -309,12 +338,15 @@ pub unsafe fn init() {
             core::mem::transmute(GetProcAddress(kernel32, c"SetConsoleOutputCP".as_ptr()));
         SetConsoleOutputCP(WinApi::CP_UTF8); // for stdout
 
+        #[cfg(not(feature = "bump-alloc"))]
         allocator::install_malloc_impl(
             dlmalloc_alloc,
             dlmalloc_alloc_zeroed,
             dlmalloc_dealloc,
             dlmalloc_realloc,
         );
+        #[cfg(feature = "bump-alloc")]
+        super::super::malloc::bump::install::<dlmalloc_windows::System>();
         services::install_single_service(
             5,
             services_override::svc_read_stdio as *const () as usize,
@@ -1,5 +1,7 @@
-static mut PLATFORM_DATA: usize = 0;
-static mut EXIT_CODE: i32 = 0;
+use super::Global;
+
+static PLATFORM_DATA: Global<usize> = Global::new(0);
+static EXIT_CODE: Global<i32> = Global::new(0);
 
 #[cfg(target_arch = "x86_64")]
 pub mod native_func {
@@ -41,19 +43,19 @@ pub struct PlatformData {
 }
 
 pub fn install(platform_data_by_loader: usize) {
-    unsafe {
-        PLATFORM_DATA = platform_data_by_loader;
-    }
+    PLATFORM_DATA.set(platform_data_by_loader);
 }
 unsafe fn addr(fn_id: usize) -> usize {
     unsafe {
-        core::ptr::read((PLATFORM_DATA + 32 + fn_id * core::mem::size_of::<usize>()) as *mut usize)
+        core::ptr::read(
+            (PLATFORM_DATA.get() + 32 + fn_id * core::mem::size_of::<usize>()) as *mut usize,
+        )
     }
 }
 pub unsafe fn install_single_service(fn_id: usize, fn_ptr: usize) {
     unsafe {
         core::ptr::write(
-            (PLATFORM_DATA + 32 + fn_id * core::mem::size_of::<usize>()) as *mut usize,
+            (PLATFORM_DATA.get() + 32 + fn_id * core::mem::size_of::<usize>()) as *mut usize,
             fn_ptr,
         )
     }
@@ -104,17 +106,45 @@ pub fn write_stdio(fd: usize, buf: &[u8]) -> usize {
         super::os::linux::syscall::write(fd, buf.as_ptr(), buf.len())
     }
 }
+/// Opens the file at `path` for reading (or for writing, creating or truncating it, if `write` is set)
+/// and returns its file descriptor. Panics if the file cannot be opened or if not running on Linux
+/// (e.g., when a binary built for Linux is run on Windows through the loader).
+#[cfg(target_os = "linux")]
+pub fn open_file(path: &str, write: bool) -> usize {
+    use super::os::linux::syscall;
+    // for `short`, we omit OS check, since it must be Linux
+    #[cfg(not(feature = "short"))]
+    assert!(
+        platform_data().env_id == ENV_ID_LINUX,
+        "File I/O is only supported on Linux"
+    );
+    let mut path_z = alloc::vec::Vec::with_capacity(path.len() + 1);
+    path_z.extend_from_slice(path.as_bytes());
+    path_z.push(0u8);
+    let flags = if write {
+        syscall::O_WRONLY | syscall::O_CREAT | syscall::O_TRUNC
+    } else {
+        syscall::O_RDONLY
+    };
+    let fd = unsafe { syscall::open(path_z.as_ptr(), flags, 0o644) };
+    assert!((fd as isize) >= 0, "Failed to open {}", path);
+    fd
+}
+#[cfg(target_os = "linux")]
+pub fn close_file(fd: usize) {
+    unsafe {
+        super::os::linux::syscall::close(fd);
+    }
+}
 pub fn platform_data() -> PlatformData {
     unsafe {
-        let pd: *const PlatformData = PLATFORM_DATA as *const PlatformData;
+        let pd: *const PlatformData = PLATFORM_DATA.get() as *const PlatformData;
         core::ptr::read_unaligned(pd)
     }
 }
 pub fn get_exit_status() -> i32 {
-    unsafe { EXIT_CODE }
+    EXIT_CODE.get()
 }
 pub fn set_exit_status(code: i32) {
-    unsafe {
-        EXIT_CODE = code;
-    }
+    EXIT_CODE.set(code);
 }
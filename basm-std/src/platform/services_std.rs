@@ -1,22 +1,51 @@
+use std::cell::RefCell;
+use std::fs::File;
 use std::io::{Read, Write};
+use std::mem::ManuallyDrop;
+use std::os::fd::{FromRawFd, IntoRawFd};
 use std::{io, process};
 
+type Stdio = (Box<dyn Read>, Box<dyn Write>);
+
+thread_local! {
+    static REDIRECT: RefCell<Option<Stdio>> = const { RefCell::new(None) };
+}
+
+/// Redirects standard input and output of the current thread, e.g., to pipes connected to a judge.
+/// Passing `None` restores them (and drops the previous redirection targets).
+pub fn redirect_stdio(stdio: Option<Stdio>) {
+    REDIRECT.with_borrow_mut(|r| *r = stdio);
+}
+
 #[inline(always)]
 pub fn exit(status: i32) -> ! {
     process::exit(status)
 }
 #[inline(always)]
 pub fn read_stdio(fd: usize, buf: &mut [u8]) -> usize {
+    if fd == 0
+        && let Some(n) = REDIRECT.with_borrow_mut(|r| r.as_mut().map(|(i, _)| i.read(buf)))
+    {
+        return n.unwrap_or_default();
+    }
     match fd {
         0 => {
             let mut stdin = io::stdin();
             stdin.read(buf).unwrap_or_default()
         }
-        _ => 0,
+        _ => {
+            let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd as i32) });
+            file.read(buf).unwrap_or_default()
+        }
     }
 }
 #[inline(always)]
 pub fn write_stdio(fd: usize, buf: &[u8]) -> usize {
+    if fd == 1
+        && let Some(n) = REDIRECT.with_borrow_mut(|r| r.as_mut().map(|(_, o)| o.write_all(buf)))
+    {
+        return n.map_or(0, |_| buf.len());
+    }
     match fd {
         1 => {
             let mut stdout = io::stdout();
@@ -26,6 +55,21 @@ pub fn write_stdio(fd: usize, buf: &[u8]) -> usize {
             let mut stderr = io::stderr();
             stderr.write(buf).unwrap_or_default()
         }
-        _ => 0,
+        _ => {
+            let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd as i32) });
+            file.write_all(buf).map_or(0, |_| buf.len())
+        }
     }
 }
+pub fn open_file(path: &str, write: bool) -> usize {
+    let file = if write {
+        File::create(path)
+    } else {
+        File::open(path)
+    };
+    file.unwrap_or_else(|_| panic!("Failed to open {}", path))
+        .into_raw_fd() as usize
+}
+pub fn close_file(fd: usize) {
+    drop(unsafe { File::from_raw_fd(fd as i32) });
+}
@@ -0,0 +1,48 @@
+//! Helpers for running code against a given input or an interactive judge in the tests of this crate,
+//! by redirecting the standard input and output used by [`Reader`](super::io::Reader) and
+//! [`Writer`](super::io::Writer) for the current thread. Solutions are tested with the `basm-testing` crate instead.
+use std::cell::RefCell;
+use std::io::{BufReader, PipeReader, PipeWriter, Write};
+use std::rc::Rc;
+
+use super::services::redirect_stdio;
+
+/// Runs `solution` on the current thread with `input` as the standard input, and returns its standard output.
+pub fn run_with_input(input: &[u8], solution: impl FnOnce()) -> Vec<u8> {
+    struct Capture(Rc<RefCell<Vec<u8>>>);
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let out = Rc::new(RefCell::new(Vec::new()));
+    let stdin = std::io::Cursor::new(input.to_vec());
+    redirect_stdio(Some((Box::new(stdin), Box::new(Capture(out.clone())))));
+    solution();
+    redirect_stdio(None);
+    out.take()
+}
+
+/// Runs `solution` on the current thread against `judge` on another thread,
+/// connected by a pair of pipes. Returns the result of `judge`.
+///
+/// The judge sees EOF on its input once `solution` returns.
+pub fn run_interactive<T: Send + 'static>(
+    solution: impl FnOnce(),
+    judge: impl FnOnce(&mut BufReader<PipeReader>, &mut PipeWriter) -> T + Send + 'static,
+) -> T {
+    let (judge_in, sol_out) = std::io::pipe().unwrap();
+    let (sol_in, judge_out) = std::io::pipe().unwrap();
+    let handle = std::thread::spawn(move || {
+        let mut judge_out = judge_out;
+        judge(&mut BufReader::new(judge_in), &mut judge_out)
+    });
+    redirect_stdio(Some((Box::new(sol_in), Box::new(sol_out))));
+    solution();
+    redirect_stdio(None);
+    handle.join().unwrap()
+}
@@ -0,0 +1,298 @@
+use core::ops::{Add, Sub};
+use core::time::Duration;
+
+/// A measurement of a monotonically nondecreasing clock, similar to `std::time::Instant`.
+///
+/// The clock is read from a counter of the CPU (the timestamp counter on x86 and x86-64),
+/// or from `performance.now()` on WebAssembly. Except on AArch64, where the CPU provides the frequency,
+/// the first call takes about 2 ms to calibrate the counter against the OS clock
+/// (`clock_gettime` on Linux, `QueryPerformanceCounter` on Windows).
+/// ```no_run
+/// use basm_std::platform::time::Instant;
+/// let start = Instant::now();
+/// // ...
+/// let ms = start.elapsed().as_millis();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Returns the current time.
+    pub fn now() -> Self {
+        Self(clock::now_ns())
+    }
+    /// Returns the time elapsed from `earlier` to `self`, or zero if `earlier` is later than `self`.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0))
+    }
+    /// Returns the time elapsed since `self`.
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+    /// Returns the raw reading of the clock in nanoseconds, whose origin is unspecified.
+    pub(crate) fn as_nanos(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+    fn add(self, rhs: Duration) -> Instant {
+        Instant(self.0 + rhs.as_nanos() as u64)
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+    fn sub(self, rhs: Instant) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+/// A time limit for anytime algorithms such as simulated annealing or beam search,
+/// measured from the creation of the `TimeBudget`.
+/// ```no_run
+/// use basm_std::platform::time::TimeBudget;
+/// use core::time::Duration;
+/// let budget = TimeBudget::new(Duration::from_millis(1900));
+/// while !budget.is_over() {
+///     let temperature = 100.0 * (1.0 - budget.progress());
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TimeBudget {
+    start: Instant,
+    limit: Duration,
+}
+
+impl TimeBudget {
+    /// Starts a new budget of `limit`.
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            limit,
+        }
+    }
+    /// Returns the time elapsed since the budget started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+    /// Returns the remaining time, or zero if the budget is used up.
+    pub fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.elapsed())
+    }
+    /// Returns `true` if the budget is used up.
+    pub fn is_over(&self) -> bool {
+        self.elapsed() >= self.limit
+    }
+    /// Returns the fraction of the budget used so far, clamped to `[0, 1]`.
+    pub fn progress(&self) -> f64 {
+        if self.limit.is_zero() {
+            return 1.0;
+        }
+        (self.elapsed().as_secs_f64() / self.limit.as_secs_f64()).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod clock {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static BASE: OnceLock<Instant> = OnceLock::new();
+
+    pub fn now_ns() -> u64 {
+        BASE.get_or_init(Instant::now).elapsed().as_nanos() as u64
+    }
+}
+
+#[cfg(not(test))]
+mod clock {
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    use crate::platform::{Global, services};
+
+    /// Converts `ticks` of a counter running at `freq` Hz to nanoseconds.
+    #[allow(dead_code)]
+    fn ticks_to_ns(ticks: u64, freq: u64) -> u64 {
+        (ticks as u128 * 1_000_000_000 / freq as u128) as u64
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn now_ns() -> u64 {
+        (crate::platform::os::wasm32::time_now() * 1e6) as u64
+    }
+
+    /// Reads the virtual counter, whose frequency is provided by the CPU.
+    #[cfg(target_arch = "aarch64")]
+    pub fn now_ns() -> u64 {
+        let (cnt, freq): (u64, u64);
+        unsafe {
+            core::arch::asm!("mrs {}, cntvct_el0", out(reg) cnt);
+            core::arch::asm!("mrs {}, cntfrq_el0", out(reg) freq);
+        }
+        ticks_to_ns(cnt, freq)
+    }
+
+    /// The frequency of the counter in Hz, calibrated on first use.
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    static COUNTER_FREQ: Global<u64> = Global::lazy(calibrate);
+
+    /// Reads the counter, which is much cheaper than asking the OS (a system call on Linux).
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    pub fn now_ns() -> u64 {
+        ticks_to_ns(read_counter(), COUNTER_FREQ.get())
+    }
+
+    /// How long the counter is measured against the OS clock.
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    const CALIBRATION_NS: u64 = 2_000_000;
+
+    /// Measures the frequency of the counter against the OS clock, falling back to
+    /// [`estimate_freq`] if the OS clock is unavailable (e.g., a system call denied by the judge).
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    fn calibrate() -> u64 {
+        /* Reads the OS clock along with the counter, taking the midpoint of the counter readings around it */
+        fn sample() -> Option<(u64, u64)> {
+            let before = read_counter();
+            let ns = os_now_ns()?;
+            let after = read_counter();
+            Some((ns, before + (after - before) / 2))
+        }
+        let Some((ns0, cnt0)) = sample() else {
+            return estimate_freq();
+        };
+        loop {
+            let Some((ns1, cnt1)) = sample() else {
+                return estimate_freq();
+            };
+            if ns1 - ns0 >= CALIBRATION_NS {
+                return ((cnt1 - cnt0) as u128 * 1_000_000_000 / (ns1 - ns0) as u128) as u64;
+            }
+        }
+    }
+
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    fn os_now_ns() -> Option<u64> {
+        // for `short`, we omit OS check, since it must be Linux
+        #[cfg(not(feature = "short"))]
+        let env_id = services::platform_data().env_id;
+        #[cfg(feature = "short")]
+        let env_id = services::ENV_ID_LINUX;
+        match env_id {
+            services::ENV_ID_LINUX => linux_now_ns(),
+            #[cfg(not(any(target_arch = "riscv64", feature = "short")))]
+            services::ENV_ID_WINDOWS => Some(windows_now_ns()),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(target_arch = "wasm32", target_arch = "aarch64")))]
+    fn linux_now_ns() -> Option<u64> {
+        use crate::platform::os::linux::syscall;
+        let mut ts: syscall::Timespec = Default::default();
+        let ret = unsafe { syscall::clock_gettime(syscall::CLOCK_MONOTONIC, &mut ts) };
+        if (ret as isize) < 0 {
+            return None;
+        }
+        Some(ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64)
+    }
+
+    #[cfg(not(any(
+        target_arch = "wasm32",
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        feature = "short"
+    )))]
+    fn windows_now_ns() -> u64 {
+        use crate::platform::os::windows::WINAPI;
+        static FREQ: Global<i64> = Global::lazy(|| {
+            let mut freq = 0i64;
+            unsafe {
+                (*core::ptr::addr_of!(WINAPI)).QueryPerformanceFrequency(&mut freq);
+            }
+            freq
+        });
+        let mut count = 0i64;
+        unsafe {
+            (*core::ptr::addr_of!(WINAPI)).QueryPerformanceCounter(&mut count);
+        }
+        ticks_to_ns(count as u64, FREQ.get() as u64)
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+    fn read_counter() -> u64 {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::_rdtsc;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::_rdtsc;
+        unsafe { _rdtsc() }
+    }
+
+    /// Estimates the frequency of the timestamp counter using CPUID (falling back to 3 GHz
+    /// if the CPU does not report it). This is only used if there is no OS clock to calibrate against.
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+    fn estimate_freq() -> u64 {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::__cpuid;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::__cpuid;
+        let max_leaf = __cpuid(0).eax;
+        if max_leaf >= 0x15 {
+            // TSC frequency as a ratio to the core crystal clock
+            let r = __cpuid(0x15);
+            if r.eax != 0 && r.ebx != 0 && r.ecx != 0 {
+                return r.ecx as u64 * r.ebx as u64 / r.eax as u64;
+            }
+        }
+        if max_leaf >= 0x16 && __cpuid(0x16).eax != 0 {
+            // processor base frequency in MHz, which is close to the TSC frequency on Intel CPUs
+            return __cpuid(0x16).eax as u64 * 1_000_000;
+        }
+        3_000_000_000
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    fn read_counter() -> u64 {
+        let cnt: u64;
+        unsafe {
+            core::arch::asm!("csrr {}, time", out(reg) cnt);
+        }
+        cnt
+    }
+
+    /// The frequency of the `time` CSR is given only by the device tree, so we assume 10 MHz,
+    /// which is used by QEMU and most boards. This is only used if `clock_gettime` fails.
+    #[cfg(target_arch = "riscv64")]
+    fn estimate_freq() -> u64 {
+        10_000_000
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn instant_is_monotonic() {
+        let a = Instant::now();
+        std::thread::sleep(Duration::from_millis(5));
+        let b = Instant::now();
+        assert!(b > a);
+        assert!(b - a >= Duration::from_millis(5));
+        assert_eq!(a - b, Duration::ZERO);
+        assert!(a + Duration::from_millis(5) <= b);
+    }
+
+    #[test]
+    fn time_budget() {
+        let budget = TimeBudget::new(Duration::from_millis(20));
+        assert!(!budget.is_over());
+        assert!(budget.progress() < 1.0);
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(budget.is_over());
+        assert_eq!(budget.remaining(), Duration::ZERO);
+        assert_eq!(budget.progress(), 1.0);
+        assert!(TimeBudget::new(Duration::ZERO).is_over());
+    }
+}
@@ -0,0 +1,238 @@
+//! Static thread-local storage for the main thread, so that `#[thread_local]` statics
+//! (including those in dependencies) can be used.
+//!
+//! Since there is no dynamic linker, we set up the TLS block ourselves from the `PT_TLS` segment.
+//! When running natively, it is found through the program headers at `__ehdr_start`. With the loader,
+//! the headers are not part of the image, so `static-pie-elf2bin.py` records the segment
+//! in the dynamic section instead. The linker resolves all thread-local accesses of a static-pie
+//! to fixed offsets from the thread pointer, so a single block laid out as the psABI
+//! of each architecture specifies is all we need.
+//!
+//! This is only built with the `tls` feature, so that solutions without thread-locals pay nothing for it.
+//! Since an image with a `PT_TLS` segment would otherwise access memory through the thread pointer
+//! of the host program, `static-pie-elf2bin.py` rejects such images unless they export `_basm_tls_runtime`.
+//!
+//! TLS requires Linux, since the block is mapped with `mmap` rather than taken from the allocator
+//! (so that the allocator is not linked merely for this), and installing the thread pointer
+//! on x86 and x86-64 requires a syscall. Since the host program keeps using its own thread pointer
+//! in fn-impl, TLS is not supported there. `static-pie-gen.py` rejects such builds,
+//! and makes the generated code fail to compile on Windows.
+
+use super::os::linux::syscall;
+use super::services;
+
+const PT_TLS: u32 = 7;
+
+#[cfg(target_pointer_width = "64")]
+#[allow(dead_code)]
+#[repr(C)]
+struct ElfPhdr {
+    p_type: u32,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+}
+
+#[cfg(target_pointer_width = "32")]
+#[allow(dead_code)]
+#[repr(C)]
+struct ElfPhdr {
+    p_type: u32,
+    p_offset: u32,
+    p_vaddr: u32,
+    p_paddr: u32,
+    p_filesz: u32,
+    p_memsz: u32,
+    p_flags: u32,
+    p_align: u32,
+}
+
+/// The offsets of `e_phoff`, `e_phentsize`, and `e_phnum` in the ELF header.
+#[cfg(target_pointer_width = "64")]
+const EHDR_PH_OFFSETS: (usize, usize, usize) = (32, 54, 56);
+#[cfg(target_pointer_width = "32")]
+const EHDR_PH_OFFSETS: (usize, usize, usize) = (28, 42, 44);
+
+/// Dynamic section entries added by `static-pie-elf2bin.py`, holding `p_vaddr`, `p_filesz`, `p_memsz`,
+/// and `p_align` of the `PT_TLS` segment, respectively.
+const DT_BASM_TLS: [usize; 4] = [0x6ba5_0000, 0x6ba5_0001, 0x6ba5_0002, 0x6ba5_0003];
+const DT_NULL: usize = 0;
+
+/// Exported to tell `static-pie-elf2bin.py` that the image sets up its TLS block.
+#[unsafe(no_mangle)]
+#[allow(non_upper_case_globals)]
+static _basm_tls_runtime: u8 = 0;
+
+unsafe extern "C" {
+    static __ehdr_start: u8;
+    static _DYNAMIC: [usize; 0];
+}
+
+/// The initialization image of the TLS block, as given by the `PT_TLS` segment.
+struct TlsTemplate {
+    vaddr: usize,
+    filesz: usize,
+    memsz: usize,
+    align: usize,
+}
+
+/// Finds the `PT_TLS` segment through the program headers.
+unsafe fn find_tls_segment(ehdr: *const u8) -> Option<TlsTemplate> {
+    unsafe {
+        let (phoff, phentsize, phnum) = EHDR_PH_OFFSETS;
+        let e_phoff = (ehdr.add(phoff) as *const usize).read_unaligned();
+        let e_phentsize = (ehdr.add(phentsize) as *const u16).read_unaligned() as usize;
+        let e_phnum = (ehdr.add(phnum) as *const u16).read_unaligned() as usize;
+        (0..e_phnum)
+            .map(|i| &*(ehdr.add(e_phoff + i * e_phentsize) as *const ElfPhdr))
+            .find(|ph| ph.p_type == PT_TLS)
+            .map(|ph| TlsTemplate {
+                vaddr: ph.p_vaddr as usize,
+                filesz: ph.p_filesz as usize,
+                memsz: ph.p_memsz as usize,
+                align: ph.p_align as usize,
+            })
+    }
+}
+
+/// Finds the `PT_TLS` segment through the entries recorded in the dynamic section.
+unsafe fn find_tls_dynamic(dynamic: *const usize) -> Option<TlsTemplate> {
+    unsafe {
+        let mut values = [None; 4];
+        let mut p = dynamic;
+        while *p != DT_NULL {
+            if let Some(i) = DT_BASM_TLS.iter().position(|&tag| tag == *p) {
+                values[i] = Some(*p.add(1));
+            }
+            p = p.add(2);
+        }
+        let [Some(vaddr), Some(filesz), Some(memsz), Some(align)] = values else {
+            return None;
+        };
+        Some(TlsTemplate {
+            vaddr,
+            filesz,
+            memsz,
+            align,
+        })
+    }
+}
+
+/// The size of the thread control block. Only its first word (a pointer to itself) is used on x86,
+/// but we reserve a few more words since code may read fixed offsets of the TCB (e.g., the stack guard at `fs:0x28`).
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+const TCB_SIZE: usize = 16 * core::mem::size_of::<usize>();
+
+/// Returns the size of the allocation, the offset of the TLS block, and the offset of the thread pointer.
+///
+/// On x86 and x86-64 (variant II), the TLS block ends at the thread pointer, which points to the TCB.
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+fn layout(memsz: usize, align: usize) -> (usize, usize, usize) {
+    let tp = memsz.next_multiple_of(align);
+    (tp + TCB_SIZE, 0, tp)
+}
+/// On AArch64 (variant I), the thread pointer points to a 16-byte TCB, which is followed by the TLS block.
+#[cfg(target_arch = "aarch64")]
+fn layout(memsz: usize, align: usize) -> (usize, usize, usize) {
+    let block = 16usize.next_multiple_of(align);
+    (block + memsz, block, 0)
+}
+/// On RISC-V (variant I with an empty TCB), the thread pointer points to the TLS block itself.
+#[cfg(target_arch = "riscv64")]
+fn layout(memsz: usize, _align: usize) -> (usize, usize, usize) {
+    (memsz, 0, 0)
+}
+
+/// Installs `tp` as the thread pointer. Returns `false` if the syscall fails.
+#[cfg(target_arch = "x86_64")]
+unsafe fn set_thread_pointer(tp: usize) -> bool {
+    unsafe {
+        /* `fs:0` holds the thread pointer itself, from which the code computes TLS addresses */
+        *(tp as *mut usize) = tp;
+        syscall::arch_prctl(syscall::ARCH_SET_FS, tp) == 0
+    }
+}
+#[cfg(target_arch = "x86")]
+unsafe fn set_thread_pointer(tp: usize) -> bool {
+    unsafe {
+        /* `gs:0` holds the thread pointer itself, from which the code computes TLS addresses */
+        *(tp as *mut usize) = tp;
+        /* A flat 4 GiB data segment based at `tp` (seg_32bit, limit_in_pages, useable) */
+        let mut desc = syscall::UserDesc {
+            entry_number: u32::MAX,
+            base_addr: tp as u32,
+            limit: 0xfffff,
+            flags: 0x51,
+        };
+        if syscall::set_thread_area(&mut desc) != 0 {
+            return false;
+        }
+        let selector = (desc.entry_number << 3 | 3) as u16;
+        core::arch::asm!("mov gs, {0:x}", in(reg) selector, options(nostack, preserves_flags));
+        true
+    }
+}
+#[cfg(target_arch = "aarch64")]
+unsafe fn set_thread_pointer(tp: usize) -> bool {
+    unsafe {
+        core::arch::asm!("msr tpidr_el0, {}", in(reg) tp, options(nostack, preserves_flags));
+    }
+    true
+}
+#[cfg(target_arch = "riscv64")]
+unsafe fn set_thread_pointer(tp: usize) -> bool {
+    unsafe {
+        core::arch::asm!("mv tp, {}", in(reg) tp, options(nostack, preserves_flags));
+    }
+    true
+}
+
+/// Sets up the TLS block of the main thread if the image has a `PT_TLS` segment.
+///
+/// # Safety
+/// This must be called once, after relocations are applied and before any thread-local is accessed.
+pub unsafe fn init() {
+    unsafe {
+        let ehdr = core::ptr::addr_of!(__ehdr_start);
+        let tls = if super::is_local_env() {
+            find_tls_segment(ehdr)
+        } else {
+            find_tls_dynamic(core::ptr::addr_of!(_DYNAMIC) as *const usize)
+        };
+        let Some(tls) = tls else {
+            return;
+        };
+        let pd = services::platform_data();
+        /* In fn-impl, the thread (and its thread pointer) belongs to the host program we return to */
+        if pd.env_flags & services::ENV_FLAGS_NO_EXIT != 0 {
+            panic!("thread-local storage is not supported for fn-impl");
+        }
+        if pd.env_id != services::ENV_ID_LINUX {
+            panic!("thread-local storage is only supported on Linux");
+        }
+        let align = tls.align.max(core::mem::align_of::<usize>());
+        let (size, block, tp) = layout(tls.memsz, align);
+        /* Fresh anonymous pages are zeroed; over-allocate in case `align` exceeds the page size */
+        let map = syscall::mmap(
+            core::ptr::null(),
+            size + align,
+            syscall::PROT_READ | syscall::PROT_WRITE,
+            syscall::MAP_ANON | syscall::MAP_PRIVATE,
+            -1,
+            0,
+        );
+        if syscall::is_err_ptr(map) {
+            panic!("failed to allocate thread-local storage");
+        }
+        let base = map.add(map.align_offset(align));
+        /* The initialization image (.tdata) has already been relocated; the rest (.tbss) stays zeroed */
+        core::ptr::copy_nonoverlapping(ehdr.add(tls.vaddr), base.add(block), tls.filesz);
+        if !set_thread_pointer(base as usize + tp) {
+            panic!("failed to install the thread pointer");
+        }
+    }
+}
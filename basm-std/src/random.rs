@@ -0,0 +1,183 @@
+//! Random number generation seeded from the entropy available on the platform.
+//!
+//! Use this for randomized algorithms (e.g., treaps) and for hashing that must not be
+//! predictable by anti-hash tests. The generators are from the `rand` crate, so the
+//! `rand::Rng` and `rand::seq::SliceRandom` traits can be used as well.
+//! ```no_run
+//! use basm_std::random;
+//! let mut a = vec![1, 2, 3, 4, 5];
+//! random::shuffle(&mut a);
+//! let pivot = a[random::gen_range(0..a.len())];
+//! let h = random::hash_u64(pivot);
+//! ```
+use crate::platform::time::Instant;
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use rand::distr::uniform::{SampleRange, SampleUniform};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// The splitmix64 finalizer, a bijective mixing function on `u64`.
+pub const fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Returns 64 bits from the operating system (`getrandom` on Linux), or 0 if unavailable.
+#[cfg(not(any(test, target_arch = "wasm32")))]
+fn os_entropy() -> u64 {
+    // for `short`, we omit OS check, since it must be Linux
+    #[cfg(not(feature = "short"))]
+    {
+        use crate::platform::services;
+        if services::platform_data().env_id != services::ENV_ID_LINUX {
+            return 0;
+        }
+    }
+    use crate::platform::os::linux::syscall;
+    let mut x = 0u64;
+    unsafe {
+        syscall::getrandom(
+            &mut x as *mut u64 as *mut u8,
+            core::mem::size_of::<u64>(),
+            syscall::GRND_NONBLOCK,
+        );
+    }
+    x
+}
+#[cfg(any(test, target_arch = "wasm32"))]
+fn os_entropy() -> u64 {
+    0
+}
+
+/// Returns 64 bits from the CPU (`rdrand` if supported, and `rdtsc`), or 0 if unavailable.
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+fn cpu_entropy() -> u64 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{__cpuid, _rdrand32_step as rdrand_step, _rdtsc};
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{__cpuid, _rdrand64_step as rdrand_step, _rdtsc};
+    #[allow(clippy::unnecessary_cast)] // u32 on x86
+    #[target_feature(enable = "rdrand")]
+    unsafe fn rdrand() -> u64 {
+        let mut x = 0;
+        // retry a few times, since rdrand may fail transiently
+        for _ in 0..10 {
+            if rdrand_step(&mut x) == 1 {
+                break;
+            }
+        }
+        x as u64
+    }
+    unsafe {
+        let has_rdrand = (__cpuid(1).ecx >> 30) & 1 != 0;
+        let x = if has_rdrand { rdrand() } else { 0 };
+        splitmix64(x) ^ _rdtsc()
+    }
+}
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+fn cpu_entropy() -> u64 {
+    0
+}
+
+/// Returns a 64-bit seed mixed from all entropy sources available on the platform:
+/// the operating system, the CPU, the current time, and addresses randomized by ASLR.
+///
+/// Each call returns a different value.
+pub fn entropy() -> u64 {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let stack = 0u8;
+    let heap = Box::new(0u8);
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed) as u64;
+    [
+        os_entropy(),
+        cpu_entropy(),
+        Instant::now().as_nanos() ^ counter,
+        &stack as *const u8 as u64,
+        &*heap as *const u8 as u64,
+        entropy as *const () as u64,
+    ]
+    .into_iter()
+    .fold(0, |acc, x| splitmix64(acc ^ x))
+}
+
+/// Returns a new `SmallRng` seeded with [`entropy()`].
+///
+/// Create it once and reuse it, since gathering entropy is much slower than generating numbers.
+pub fn rng() -> SmallRng {
+    SmallRng::seed_from_u64(entropy())
+}
+
+struct State {
+    rng: SmallRng,
+    hash_seed: u64,
+}
+
+impl State {
+    fn new() -> Self {
+        let mut rng = rng();
+        let hash_seed = rng.random();
+        Self { rng, hash_seed }
+    }
+}
+
+#[cfg(not(test))]
+fn with_global<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    static GLOBAL: crate::platform::Global<State> = crate::platform::Global::lazy(State::new);
+    GLOBAL.with(f)
+}
+#[cfg(test)]
+fn with_global<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    std::thread_local! {
+        static GLOBAL: core::cell::RefCell<State> = core::cell::RefCell::new(State::new());
+    }
+    GLOBAL.with_borrow_mut(f)
+}
+
+/// Shuffles `a` uniformly at random using the global generator.
+pub fn shuffle<T>(a: &mut [T]) {
+    with_global(|g| a.shuffle(&mut g.rng))
+}
+
+/// Returns a number sampled uniformly from `range` (e.g., `0..n` or `1..=n`) using the global generator.
+pub fn gen_range<T: SampleUniform, R: SampleRange<T>>(range: R) -> T {
+    with_global(|g| g.rng.random_range(range))
+}
+
+/// Returns a random seed for hashing, which is fixed for the lifetime of the process.
+pub fn hash_seed() -> u64 {
+    with_global(|g| g.hash_seed)
+}
+
+/// Hashes `x` with [`splitmix64`] and [`hash_seed()`], so that the hash values cannot be predicted in advance.
+pub fn hash_u64(x: u64) -> u64 {
+    splitmix64(x ^ hash_seed())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn random_facade() {
+        assert_ne!(entropy(), entropy());
+        assert_eq!(splitmix64(0), 0xe220a8397b1dcdaf);
+
+        let mut a: Vec<u32> = (0..100).collect();
+        shuffle(&mut a);
+        assert_ne!(a, (0..100).collect::<Vec<_>>());
+        a.sort_unstable();
+        assert_eq!(a, (0..100).collect::<Vec<_>>());
+
+        for _ in 0..100 {
+            let x = gen_range(3..=5);
+            assert!((3..=5).contains(&x));
+        }
+        assert_eq!(hash_seed(), hash_seed());
+        assert_eq!(hash_u64(42), hash_u64(42));
+        assert_ne!(hash_u64(42), hash_u64(43));
+    }
+}
@@ -0,0 +1,191 @@
+//! Helpers for testing solutions with `cargo test`, to be used as a dev-dependency.
+//!
+//! basm-std is used as is (i.e., built for the runtime rather than on the standard library),
+//! with its platform services provided by this crate in place of the loader.
+//! The standard input and output used by [`Reader`](basm_std::platform::io::Reader) and
+//! [`Writer`](basm_std::platform::io::Writer) can be redirected for the current thread,
+//! so that a solution can be run against a given input or an interactive judge.
+//! Note that a `Writer` must be flushed (e.g., dropped) before the solution returns.
+//! ```
+//! use basm_std::platform::io::{Print, Reader, ReaderTrait, Writer};
+//! use basm_testing::run_with_input;
+//! let out = run_with_input(b"1 2\n", || {
+//!     let mut reader: Reader = Default::default();
+//!     let mut writer: Writer = Default::default();
+//!     writer.println(reader.i32() + reader.i32());
+//! });
+//! assert_eq!(out, b"3\n");
+//! ```
+//!
+//! Since the runtime assumes a single thread, run tests with `--test-threads 1`
+//! if solutions keep global state (e.g., in [`Global`](basm_std::platform::Global)).
+use basm_std::platform::services::{self, PlatformData};
+use std::alloc::Layout;
+use std::cell::RefCell;
+use std::io::{BufReader, PipeReader, PipeWriter, Read, Write};
+use std::rc::Rc;
+use std::sync::Once;
+
+type Stdio = (Box<dyn Read>, Box<dyn Write>);
+
+thread_local! {
+    static REDIRECT: RefCell<Option<Stdio>> = const { RefCell::new(None) };
+}
+
+/// Defines the services with the calling convention that basm-std expects from the loader.
+macro_rules! services {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)? $body:block)*) => {$(
+        #[cfg(target_arch = "x86_64")]
+        unsafe extern "win64" fn $name($($arg: $ty),*) $(-> $ret)? $body
+        #[cfg(not(target_arch = "x86_64"))]
+        unsafe extern "C" fn $name($($arg: $ty),*) $(-> $ret)? $body
+    )*};
+}
+
+services! {
+    fn svc_alloc(size: usize, align: usize) -> *mut u8 {
+        unsafe { std::alloc::alloc(Layout::from_size_align_unchecked(size, align)) }
+    }
+    fn svc_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
+        unsafe { std::alloc::alloc_zeroed(Layout::from_size_align_unchecked(size, align)) }
+    }
+    fn svc_dealloc(ptr: *mut u8, size: usize, align: usize) {
+        unsafe { std::alloc::dealloc(ptr, Layout::from_size_align_unchecked(size, align)) }
+    }
+    fn svc_realloc(ptr: *mut u8, old_size: usize, old_align: usize, new_size: usize) -> *mut u8 {
+        unsafe {
+            std::alloc::realloc(ptr, Layout::from_size_align_unchecked(old_size, old_align), new_size)
+        }
+    }
+    fn svc_read_stdio(fd: usize, buf: *mut u8, count: usize) -> usize {
+        let buf = unsafe { std::slice::from_raw_parts_mut(buf, count) };
+        if fd != 0 {
+            return 0;
+        }
+        REDIRECT
+            .with_borrow_mut(|r| match r {
+                Some((input, _)) => input.read(buf),
+                None => std::io::stdin().read(buf),
+            })
+            .unwrap_or_default()
+    }
+    fn svc_write_stdio(fd: usize, buf: *const u8, count: usize) -> usize {
+        let buf = unsafe { std::slice::from_raw_parts(buf, count) };
+        let result = match fd {
+            1 => REDIRECT.with_borrow_mut(|r| match r {
+                Some((_, output)) => output.write_all(buf),
+                None => std::io::stdout().write_all(buf),
+            }),
+            2 => std::io::stderr().write_all(buf),
+            _ => return 0,
+        };
+        result.map_or(0, |_| buf.len())
+    }
+}
+
+/// Installs the services on first use. The environment is left unknown, so that basm-std does not
+/// bypass the services with system calls (e.g., `MmapReader` mapping the actual standard input).
+fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let pd = PlatformData {
+            env_flags: services::ENV_FLAGS_NATIVE,
+            fn_table: [
+                0,
+                svc_alloc as *const () as usize,
+                svc_alloc_zeroed as *const () as usize,
+                svc_dealloc as *const () as usize,
+                svc_realloc as *const () as usize,
+                svc_read_stdio as *const () as usize,
+                svc_write_stdio as *const () as usize,
+            ],
+            ..Default::default()
+        };
+        services::install(Box::leak(Box::new(pd)) as *const PlatformData as usize);
+    });
+}
+
+/// Redirects standard input and output of the current thread, e.g., to pipes connected to a judge.
+/// Passing `None` restores them (and drops the previous redirection targets).
+pub fn redirect_stdio(stdio: Option<Stdio>) {
+    install();
+    REDIRECT.with_borrow_mut(|r| *r = stdio);
+}
+
+/// Runs `solution` on the current thread with `input` as the standard input, and returns its standard output.
+pub fn run_with_input(input: &[u8], solution: impl FnOnce()) -> Vec<u8> {
+    struct Capture(Rc<RefCell<Vec<u8>>>);
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let out = Rc::new(RefCell::new(Vec::new()));
+    let stdin = std::io::Cursor::new(input.to_vec());
+    redirect_stdio(Some((Box::new(stdin), Box::new(Capture(out.clone())))));
+    solution();
+    redirect_stdio(None);
+    out.take()
+}
+
+/// Runs `solution` on the current thread against `judge` on another thread,
+/// connected by a pair of pipes. Returns the result of `judge`.
+///
+/// The judge sees EOF on its input once `solution` returns.
+pub fn run_interactive<T: Send + 'static>(
+    solution: impl FnOnce(),
+    judge: impl FnOnce(&mut BufReader<PipeReader>, &mut PipeWriter) -> T + Send + 'static,
+) -> T {
+    let (judge_in, sol_out) = std::io::pipe().unwrap();
+    let (sol_in, judge_out) = std::io::pipe().unwrap();
+    let handle = std::thread::spawn(move || {
+        let mut judge_out = judge_out;
+        judge(&mut BufReader::new(judge_in), &mut judge_out)
+    });
+    redirect_stdio(Some((Box::new(sol_in), Box::new(sol_out))));
+    solution();
+    redirect_stdio(None);
+    handle.join().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use basm_std::platform::io::{Interactor, MmapReader, Print, ReaderTrait};
+    use std::io::BufRead;
+
+    #[test]
+    fn mmap_reader_reads_redirected_input() {
+        let out = run_with_input(b"3\n1 2 3", || {
+            let mut reader = MmapReader::new();
+            let mut io: Interactor = Default::default();
+            let n = reader.usize();
+            io.println((0..n).map(|_| reader.i64()).sum::<i64>());
+        });
+        assert_eq!(out, b"6\n");
+    }
+
+    #[test]
+    fn interactive_judge() {
+        let answers = run_interactive(
+            || {
+                let mut io: Interactor = Default::default();
+                let n = io.u32();
+                let x: u32 = io.query(("?", n));
+                io.println(("!", x));
+            },
+            |input, output| {
+                writeln!(output, "20").unwrap();
+                let mut lines = input.lines().map(Result::unwrap);
+                assert_eq!(lines.next().unwrap(), "? 20");
+                writeln!(output, "400").unwrap();
+                lines.next().unwrap()
+            },
+        );
+        assert_eq!(answers, "! 400");
+    }
+}
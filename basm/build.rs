@@ -54,7 +54,8 @@ fn main() {
         "x86_64-unknown-linux-gnu"
         | "x86_64-unknown-linux-gnu-short"
         | "i686-unknown-linux-gnu"
-        | "aarch64-unknown-linux-gnu" => {
+        | "aarch64-unknown-linux-gnu"
+        | "riscv64gc-unknown-linux-gnu" => {
             link_args_basm.push("-nostartfiles");
             link_args_basm.push("-nostdlib");
             link_args_basm.push("-static-pie");
@@ -66,6 +67,7 @@ fn main() {
             link_args_basm.push("-Wl,--entry=_basm_start,--build-id=none,--gc-sections,--no-eh-frame-hdr,-z,norelro");
             link_args_basm.push("-Wl,--export-dynamic-symbol=_basm_export*");
             link_args_basm.push("-Wl,--export-dynamic-symbol=_basm_import*");
+            link_args_basm.push("-Wl,--export-dynamic-symbol=_basm_tls_runtime");
             link_args_basm_submit.push("-Wl,-z,max-page-size=128");
         }
         "aarch64-apple-darwin" => {
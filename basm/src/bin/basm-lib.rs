@@ -25,4 +25,16 @@ mod verify_test_works {
     fn run() {
         assert_eq!(8, add(5, 3));
     }
+
+    #[test]
+    fn run_with_input() {
+        use basm::platform::io::{Print, Reader, ReaderTrait, Writer};
+        let out = basm_testing::run_with_input(b"5 3\n", || {
+            let mut reader: Reader = Default::default();
+            let mut writer: Writer = Default::default();
+            let (x, y) = (reader.i64(), reader.i64());
+            writer.println(add(x, y));
+        });
+        assert_eq!(out, b"8\n");
+    }
 }
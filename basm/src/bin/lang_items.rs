@@ -26,7 +26,11 @@ fn panic(_pi: &core::panic::PanicInfo) -> ! {
     unsafe {
         basm::platform::codegen::print_panicinfo_and_exit(_pi)
     }
-    #[cfg(feature = "submit")]
+    #[cfg(all(feature = "submit", feature = "submit-panic"))]
+    unsafe {
+        basm::platform::codegen::exit_on_panic(_pi)
+    }
+    #[cfg(all(feature = "submit", not(feature = "submit-panic")))]
     unsafe {
         core::hint::unreachable_unchecked()
     }
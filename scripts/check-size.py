@@ -0,0 +1,42 @@
+"""
+This script builds the default solution and checks the size of the binary embedded in the generated code,
+so that runtime features which a solution does not use cannot silently bloat every submission.
+Developed for use in CI.
+Usage:
+    python scripts/check-size.py [build-cmd] [binary-path] [max-bytes]
+Example:
+    python scripts/check-size.py ./release.sh ./target/x86_64-unknown-linux-gnu/release/basm-submit-stripped.bin 8192
+"""
+
+import os
+import subprocess
+import sys
+
+if __name__ == "__main__":
+    try:
+        build_cmd = sys.argv[1]
+        binary_path = sys.argv[2]
+        max_bytes = int(sys.argv[3])
+    except:
+        raise Exception("\n".join([
+            "",
+            "",
+            "**Error: incorrect argument**",
+            "",
+            "This script builds the default solution and checks the size of the binary embedded in the generated code.",
+            "Developed for use in CI.",
+            "Usage:",
+            "    python scripts/check-size.py [build-cmd] [binary-path] [max-bytes]",
+            "Example:",
+            "    python scripts/check-size.py ./release.sh ./target/x86_64-unknown-linux-gnu/release/basm-submit-stripped.bin 8192"
+        ]))
+
+    p = subprocess.run(build_cmd, shell=True, capture_output=True, text=True, encoding="utf8")
+    if p.returncode != 0:
+        raise Exception("Build failed. The stderr:\n{0}".format(p.stderr))
+
+    size = os.path.getsize(binary_path)
+    print(f"{binary_path}: {size} bytes (limit: {max_bytes} bytes)")
+    if size > max_bytes:
+        raise Exception(f"The binary of the default solution exceeds {max_bytes} bytes; " +
+            "check whether a runtime feature is linked even though the solution does not use it")
@@ -86,6 +86,12 @@ ET_EXEC         = 2
 ET_DYN          = 3
 ET_CORE         = 4
 
+# e_machine
+EM_386          = 3
+EM_X86_64       = 62
+EM_AARCH64      = 183
+EM_RISCV        = 243
+
 # shn
 SHN_UNDEF       = 0
 
@@ -102,6 +108,7 @@ SHT_NOBITS      = 8
 SHT_REL         = 9
 SHT_SHLIB       = 10
 SHT_DYNSYM      = 11
+SHT_RELR        = 19
 SHT_LOOS        = 0x60000000
 SHT_HIOS        = 0x6FFFFFFF
 SHT_LOPROC      = 0x70000000
@@ -109,6 +116,13 @@ SHT_HIPROC      = 0x7FFFFFFF
 SHT_LOUSER      = 0x80000000
 SHT_HIUSER      = 0xFFFFFFFF
 
+# p_type
+PT_TLS          = 7
+
+# Dynamic section entries recording p_vaddr, p_filesz, p_memsz, and p_align of the PT_TLS segment
+# (must be kept in sync with basm-std/src/platform/tls.rs)
+DT_BASM_TLS     = [0x6ba50000, 0x6ba50001, 0x6ba50002, 0x6ba50003]
+
 # sh_flags
 SHF_WRITE       = 1
 SHF_ALLOC       = 2
@@ -117,6 +131,45 @@ SHF_MASKOS      = 0x0FF00000
 SHF_MASKPROC    = 0xF0000000
 
 
+# Relocation types supported by the loaders (basm-std/src/platform/loader)
+SUPPORTED_RELOCATIONS = {
+    EM_X86_64: {
+        0: "R_X86_64_NONE", 1: "R_X86_64_64", 6: "R_X86_64_GLOB_DAT", 7: "R_X86_64_JUMP_SLOT",
+        8: "R_X86_64_RELATIVE", 37: "R_X86_64_IRELATIVE",
+    },
+    EM_386: {
+        0: "R_386_NONE", 1: "R_386_32", 6: "R_386_GLOB_DAT", 7: "R_386_JMP_SLOT",
+        8: "R_386_RELATIVE", 42: "R_386_IRELATIVE",
+    },
+    EM_AARCH64: {
+        0: "R_AARCH64_NONE", 257: "R_AARCH64_ABS64", 1025: "R_AARCH64_GLOB_DAT", 1026: "R_AARCH64_JUMP_SLOT",
+        1027: "R_AARCH64_RELATIVE", 1032: "R_AARCH64_IRELATIVE",
+    },
+    EM_RISCV: {
+        0: "R_RISCV_NONE", 2: "R_RISCV_64", 3: "R_RISCV_RELATIVE", 5: "R_RISCV_JUMP_SLOT",
+        58: "R_RISCV_IRELATIVE",
+    },
+}
+# Relocation types that refer to a symbol, which must be defined within the binary
+SYMBOL_RELOCATIONS = {
+    EM_X86_64: [1, 6, 7],
+    EM_386: [1, 6, 7],
+    EM_AARCH64: [257, 1025, 1026],
+    EM_RISCV: [2, 5],
+}
+# For `short`, the loader only supports relative relocations (and no DT_RELR)
+SHORT_RELOCATIONS = {
+    EM_X86_64: [8],
+}
+# Thread-local storage relocation types, for which we give a hint.
+# The loaders do not process them, since the linker resolves thread-local accesses within a static-pie.
+TLS_RELOCATIONS = {
+    EM_X86_64: {16: "R_X86_64_DTPMOD64", 17: "R_X86_64_DTPOFF64", 18: "R_X86_64_TPOFF64"},
+    EM_386: {14: "R_386_TLS_TPOFF", 35: "R_386_TLS_DTPMOD32", 36: "R_386_TLS_DTPOFF32", 37: "R_386_TLS_TPOFF32"},
+    EM_AARCH64: {1028: "R_AARCH64_TLS_DTPMOD", 1029: "R_AARCH64_TLS_DTPREL", 1030: "R_AARCH64_TLS_TPREL"},
+    EM_RISCV: {6: "R_RISCV_TLS_DTPMOD64", 8: "R_RISCV_TLS_DTPREL64", 11: "R_RISCV_TLS_TPREL64"},
+}
+
 def b2i(x):
     return int.from_bytes(x, byteorder='little')
 
@@ -131,7 +184,122 @@ def check_header(elf):
         (elf[EI_DATA] == ELFDATA2LSB) and \
         (b2i(elf[16:18]) == ET_DYN)
 
-def load_elf64(elf):
+def validate_relocations(elf, sh, dynsym, resolve_st_name, short):
+    """
+    Checks that the loader can process all the dynamic relocations of the binary,
+    since otherwise the generated code would crash (or, for `short`, misbehave) at runtime.
+    Returns a list of error messages, which is empty if the binary is valid.
+    """
+    e_machine = b2i(elf[18:20])
+    if e_machine not in SUPPORTED_RELOCATIONS:
+        return [f"Unsupported machine type {e_machine}"]
+    names = dict(TLS_RELOCATIONS.get(e_machine, {}))
+    names.update(SUPPORTED_RELOCATIONS[e_machine])
+    supported = SUPPORTED_RELOCATIONS[e_machine].keys()
+    if short and e_machine in SHORT_RELOCATIONS:
+        supported = SHORT_RELOCATIONS[e_machine]
+    is64 = elf[EI_CLASS] == ELFCLASS64
+
+    errors = []
+    for sh_dict in sh:
+        if (sh_dict['sh_flags'] & SHF_ALLOC) == 0:
+            continue
+        if sh_dict['sh_type'] == SHT_RELR and short:
+            errors.append("Packed relative relocations (DT_RELR) are not supported for `short`")
+        if sh_dict['sh_type'] not in [SHT_REL, SHT_RELA]:
+            continue
+        entsize = {(True, SHT_RELA): 24, (True, SHT_REL): 16, (False, SHT_RELA): 12, (False, SHT_REL): 8}[(is64, sh_dict['sh_type'])]
+        blob = elf[sh_dict['sh_offset']:sh_dict['sh_offset']+sh_dict['sh_size']]
+        for i in range(0, len(blob) - entsize + 1, entsize):
+            if is64:
+                r_offset, r_info = b2i(blob[i:i+8]), b2i(blob[i+8:i+16])
+                r_type, r_sym = r_info & 0xFFFFFFFF, r_info >> 32
+            else:
+                r_offset, r_info = b2i(blob[i:i+4]), b2i(blob[i+4:i+8])
+                r_type, r_sym = r_info & 0xFF, r_info >> 8
+            r_name = names.get(r_type, f"relocation type {r_type}")
+            if r_type not in supported:
+                msg = f"{r_name} at offset {r_offset:#x} is not supported"
+                if r_type in TLS_RELOCATIONS.get(e_machine, {}):
+                    msg += " (thread-local accesses must be resolved by the linker, since there is no dynamic TLS)"
+                elif short:
+                    msg += " for `short`; try building without `--features short`"
+                errors.append(msg)
+            elif r_type in SYMBOL_RELOCATIONS[e_machine] and \
+                (r_sym >= len(dynsym) or dynsym[r_sym]['st_shndx'] == SHN_UNDEF):
+                st_name = resolve_st_name(dynsym[r_sym]['st_name']).decode('utf8') if r_sym < len(dynsym) else ""
+                sym_desc = f"`{st_name}`" if len(st_name) > 0 else f"#{r_sym}"
+                errors.append(f"{r_name} at offset {r_offset:#x} refers to an undefined symbol {sym_desc}, but there is nothing to link against")
+    # Report each kind of error once, since there may be many relocations of the same kind
+    unique_errors = []
+    for msg in errors:
+        kind = msg.split(" at offset ")[0]
+        if all(x.split(" at offset ")[0] != kind for x in unique_errors):
+            unique_errors.append(msg)
+    return unique_errors
+
+def find_tls_segment(elf):
+    """
+    Returns (p_vaddr, p_filesz, p_memsz, p_align) of the PT_TLS segment, or None if there is none.
+    """
+    if elf[EI_CLASS] == ELFCLASS64:
+        e_phoff, e_phentsize, e_phnum = b2i(elf[32:40]), b2i(elf[54:56]), b2i(elf[56:58])
+        fields = [(16, 24), (32, 40), (40, 48), (48, 56)]
+    else:
+        e_phoff, e_phentsize, e_phnum = b2i(elf[28:32]), b2i(elf[42:44]), b2i(elf[44:46])
+        fields = [(8, 12), (16, 20), (20, 24), (28, 32)]
+    for i in range(e_phnum):
+        ph = elf[e_phoff+i*e_phentsize:][:e_phentsize]
+        if b2i(ph[0:4]) == PT_TLS:
+            return tuple(b2i(ph[x:y]) for x, y in fields)
+    return None
+
+def check_tls(tls, dynsym, resolve_st_name, short):
+    """
+    Returns a list of error messages if the image has a PT_TLS segment that the runtime cannot set up.
+    The runtime exports `_basm_tls_runtime` when it is built with the `tls` feature.
+    """
+    if tls is None:
+        return []
+    if short:
+        return ["Thread-local storage is not supported for `short`; try building without `--features short`"]
+    if all(resolve_st_name(st_dict['st_name']) != b'_basm_tls_runtime' for st_dict in dynsym):
+        return ["Thread-local storage requires the `tls` feature; try building with `--features tls`"]
+    return []
+
+def trim_dynamic(blob, is64, tls):
+    """
+    Trims the DYNAMIC section in place, leaving only relocation-related entries.
+    Since the program headers are not included in the image, the PT_TLS segment (if any)
+    is recorded as DT_BASM_TLS entries, from which the runtime sets up thread-local storage.
+    """
+    entsize = 16 if is64 else 8     # sizeof(Elf64_Dyn) or sizeof(Elf32_Dyn)
+    half = entsize // 2
+    dst = 0
+    for src in range(0, len(blob), entsize):
+        # Included entries:
+        #   DT_PLTRELSZ = 2, DT_SYMTAB = 6, DT_RELA = 7, DT_RELASZ = 8, DT_RELAENT = 9,
+        #   DT_REL = 17, DT_RELSZ = 18, DT_RELENT = 19, DT_PLTREL = 20,
+        #   DT_TEXT_REL = 22, DT_JMPREL = 23,
+        #   DT_RELRSZ = 35, DT_RELR = 36, DT_RELRENT = 37.
+        #
+        # Note: DT_RELACOUNT = 0x6fff_fff9 and DT_RELCOUNT = 0x6fff_fffa
+        #   are not included since they are redundant since
+        #   DT_RELACOUNT = DT_RELASZ/DT_RELAENT and
+        #   DT_RELCOUNT = DT_RELSZ/DT_RELENT.
+        if b2i(blob[src:src+half]) in [2, 6, 7, 8, 9, 17, 18, 19, 20, 22, 23, 35, 36, 37]:
+            blob[dst:dst+entsize] = blob[src:src+entsize]
+            dst += entsize
+    if tls is not None:
+        # leave room for the terminating DT_NULL entry
+        assert dst + (len(DT_BASM_TLS) + 1) * entsize <= len(blob), "No room to record PT_TLS in the DYNAMIC section"
+        for tag, value in zip(DT_BASM_TLS, tls):
+            blob[dst:dst+half] = tag.to_bytes(half, byteorder='little')
+            blob[dst+half:dst+entsize] = value.to_bytes(half, byteorder='little')
+            dst += entsize
+    blob[dst:] = bytearray(len(blob[dst:])) # fill remaining part with zeros
+
+def load_elf64(elf, short):
     sh = []
 
     e_shoff = b2i(elf[40:48])
@@ -171,6 +339,7 @@ def load_elf64(elf):
             pos_end = max(pos_end, sh_dict['sh_addr'] + sh_dict['sh_size'])
 
     memory_bin = bytearray(pos_end)
+    tls = find_tls_segment(elf)
     dynsym = []
     dynstr = b''
     for sh_dict in sh:
@@ -178,23 +347,7 @@ def load_elf64(elf):
         blob = elf[src_off:src_off+cnt]
 
         if sh_dict['sh_type'] == SHT_DYNAMIC:
-            # Trim the DYNAMIC section, leaving only relocation-related entries
-            # 16 == sizeof(Elf64_Dyn)
-            dst = 0 
-            for src in range(0, len(blob), 16):
-                # Included entries:
-                #   DT_PLTRELSZ = 2, DT_RELA = 7, DT_RELASZ = 8, DT_RELAENT = 9,
-                #   DT_REL = 17, DT_RELSZ = 18, DT_RELENT = 19, DT_PLTREL = 20,
-                #   DT_TEXT_REL = 22, DT_JMPREL = 23.
-                #
-                # Note: DT_RELACOUNT = 0x6fff_fff9 and DT_RELCOUNT = 0x6fff_fffa
-                #   are not included since they are redundant since
-                #   DT_RELACOUNT = DT_RELASZ/DT_RELAENT and
-                #   DT_RELCOUNT = DT_RELSZ/DT_RELENT.
-                if b2i(blob[src:src+8]) in [2, 7, 8, 9, 17, 18, 19, 20, 22, 23]:
-                    blob[dst:dst+16] = blob[src:src+16]
-                    dst += 16
-            blob[dst:] = bytearray(len(blob[dst:])) # fill remaining part with zeros
+            trim_dynamic(blob, True, tls)
         elif sh_dict['sh_type'] == SHT_DYNSYM:
             for i in range(0, sh_dict['sh_size'], 24):
                 st_entry = blob[i:][:24]
@@ -224,6 +377,9 @@ def load_elf64(elf):
             i += 1
         return dynstr[st_name:i]
 
+    errors = validate_relocations(elf, sh, dynsym, resolve_st_name, short)
+    errors += check_tls(tls, dynsym, resolve_st_name, short)
+
     exports = dict()
     for st_dict in dynsym:
         st_name_str = resolve_st_name(st_dict['st_name']).decode('utf8')
@@ -231,9 +387,9 @@ def load_elf64(elf):
             exports[st_name_str] = st_dict['st_value']
 
     entrypoint_offset = b2i(elf[24:32])
-    return memory_bin, pos_begin, entrypoint_offset, exports
+    return memory_bin, pos_begin, entrypoint_offset, exports, errors
 
-def load_elf32(elf):
+def load_elf32(elf, short):
     sh = []
 
     e_shoff = b2i(elf[32:36])
@@ -248,6 +404,7 @@ def load_elf32(elf):
             'sh_addr'   : b2i(pstSectionHeader[12:16]),
             'sh_offset' : b2i(pstSectionHeader[16:20]),
             'sh_size'   : b2i(pstSectionHeader[20:24]),
+            'sh_link'   : b2i(pstSectionHeader[24:28]),
         }
         sh.append(sh_dict)
 
@@ -258,6 +415,7 @@ def load_elf32(elf):
             pos_end = max(pos_end, sh_dict['sh_addr'] + sh_dict['sh_size'])
 
     memory_bin = bytearray(pos_end)
+    tls = find_tls_segment(elf)
     for sh_dict in sh:
         if (sh_dict['sh_flags'] & SHF_ALLOC) == 0 or sh_dict['sh_size'] == 0:
             continue
@@ -265,18 +423,45 @@ def load_elf32(elf):
             continue        # since bytearray is zero-initialized
 
         dst_off, src_off, cnt = sh_dict['sh_addr'], sh_dict['sh_offset'], sh_dict['sh_size']
-        memory_bin[dst_off:dst_off+cnt] = elf[src_off:src_off+cnt]
+        blob = elf[src_off:src_off+cnt]
+        if sh_dict['sh_type'] == SHT_DYNAMIC:
+            trim_dynamic(blob, False, tls)
+        memory_bin[dst_off:dst_off+cnt] = blob
+
+    dynsym = []
+    dynstr = b''
+    for sh_dict in sh:
+        if sh_dict['sh_type'] == SHT_DYNSYM:
+            blob = elf[sh_dict['sh_offset']:sh_dict['sh_offset']+sh_dict['sh_size']]
+            for i in range(0, sh_dict['sh_size'], 16):
+                st_entry = blob[i:][:16]
+                dynsym.append({
+                    'st_name'   : b2i(st_entry[ 0: 4]),
+                    'st_value'  : b2i(st_entry[ 4: 8]),
+                    'st_shndx'  : b2i(st_entry[14:16]),
+                })
+            if sh_dict['sh_link'] < len(sh):
+                strtab = sh[sh_dict['sh_link']]
+                dynstr = bytes(elf[strtab['sh_offset']:strtab['sh_offset']+strtab['sh_size']])
+    def resolve_st_name(st_name):
+        if st_name >= len(dynstr):
+            return b''
+        return dynstr[st_name:].split(b'\0')[0]
+    errors = validate_relocations(elf, sh, dynsym, resolve_st_name, short)
+    errors += check_tls(tls, dynsym, resolve_st_name, short)
 
     entrypoint_offset = b2i(elf[24:28])
     exports = dict()        # TBD
-    return memory_bin, pos_begin, entrypoint_offset, exports
+    return memory_bin, pos_begin, entrypoint_offset, exports, errors
 
 
 if __name__ == '__main__':
+    short = "--short" in sys.argv
+    argv = [x for x in sys.argv if x != "--short"]
     try:
-        elf_path, binary_path = sys.argv[1:]
+        elf_path, binary_path = argv[1:]
     except ValueError:
-        print(f"Usage: {sys.argv[0]} elf_path binary_path", file=sys.stderr)
+        print(f"Usage: {sys.argv[0]} elf_path binary_path [--short]", file=sys.stderr)
         sys.exit(1)
 
     with open(elf_path, "rb") as f:
@@ -287,13 +472,19 @@ if __name__ == '__main__':
         sys.exit(1)
 
     if elf[EI_CLASS] == ELFCLASS64:
-        memory_bin, pos_begin, entrypoint_offset, exports = load_elf64(elf)
+        memory_bin, pos_begin, entrypoint_offset, exports, errors = load_elf64(elf, short)
     elif elf[EI_CLASS] == ELFCLASS32:
-        memory_bin, pos_begin, entrypoint_offset, exports = load_elf32(elf)
+        memory_bin, pos_begin, entrypoint_offset, exports, errors = load_elf32(elf, short)
     else:
         print(f"Unsupported EI_CLASS value: {elf[EI_CLASS]}", file=sys.stderr)
         sys.exit(1)
 
+    if len(errors) > 0:
+        print(f"The binary contains features that basm-rs cannot support at runtime:", file=sys.stderr)
+        for msg in errors:
+            print(f"  - {msg}", file=sys.stderr)
+        sys.exit(1)
+
     if pos_begin == len(elf):
         pos_begin = 0
     pos_begin -= pos_begin % 128
@@ -321,4 +512,5 @@ if __name__ == '__main__':
     fdict = {}
     fdict['entrypoint_offset'] = entrypoint_offset
     fdict['exports'] = exports
+    fdict['tls'] = find_tls_segment(elf) is not None
     print(json.dumps(fdict))    # callers of this script can capture stdout to get this value
\ No newline at end of file
@@ -32,10 +32,18 @@ if target_name in ["x86_64-pc-windows-msvc", "x86_64-pc-windows-gnu"]:
 else:
     binary_path = elf_path + ".bin"
     compressed_binary_path = binary_path + ".lzma"
-    elf2bin = subprocess.check_output([sys.executable, "scripts/static-pie-elf2bin.py", elf_path, binary_path]).decode("utf-8")
+    short = ["--short"] if target_name.endswith("-short") else []
+    elf2bin = subprocess.check_output([sys.executable, "scripts/static-pie-elf2bin.py", elf_path, binary_path] + short).decode("utf-8")
 loader_fdict = json.loads(elf2bin)
 assert 'entrypoint_offset' in loader_fdict
 
+# Thread-local storage needs a thread pointer of our own (see basm-std/src/platform/tls.rs).
+# It cannot be installed on Windows, and in fn-impl the thread belongs to the host program.
+has_tls = loader_fdict.get('tls', False)
+if has_tls and "fn-impl" in template_path:
+    print("Thread-local storage is not supported for fn-impl, since control returns to the host program", file=sys.stderr)
+    sys.exit(1)
+
 # Please refer to the following link for the lzma file format:
 #   https://svn.python.org/projects/external/xz-5.0.3/doc/lzma-file-format.txt
 # However, we use a different format:
@@ -161,4 +169,10 @@ for each_template_path in template_candidates:
     })
     if out is None or len(out_candidate) < len(out):
         out = out_candidate
+if has_tls:
+    if lang_name == "C":
+        tls_guard = '#ifdef _WIN32\n#error "Thread-local storage is not supported on Windows"\n#endif\n'
+    else:
+        tls_guard = '#[cfg(windows)]\ncompile_error!("Thread-local storage is not supported on Windows");\n'
+    out = out.replace("// LOADER BEGIN\n", "// LOADER BEGIN\n" + tls_guard, 1)
 print(out, end='')
\ No newline at end of file
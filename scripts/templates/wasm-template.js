@@ -7,7 +7,8 @@ const svc_read_stdio = (fd, buf, count) =>
   fs.readSync(fd, new Uint8Array(exports.memory.buffer, buf, count))
 const svc_write_stdio = (fd, buf, count) =>
   fs.writeSync(fd, new Uint8Array(exports.memory.buffer, buf, count))
-WebAssembly.instantiate(code, { env: { svc_read_stdio, svc_write_stdio } }).then(
+const svc_time_now = () => performance.now()
+WebAssembly.instantiate(code, { env: { svc_read_stdio, svc_write_stdio, svc_time_now } }).then(
   (wasm) => {
     exports = wasm.instance.exports
     exports._basm_start()
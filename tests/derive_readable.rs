@@ -0,0 +1,35 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use basm::platform::io::*;
+use basm_macro::Readable;
+
+#[derive(Readable)]
+struct Point(i32, i32);
+
+#[derive(Readable)]
+struct Query {
+    name: String,
+    origin: Point,
+    n: usize,
+    #[read(len = n)]
+    values: Vec<i64>,
+    #[read(skip)]
+    visited: bool,
+}
+
+pub fn main() {
+    let mut reader: Reader = Default::default();
+    let mut writer: Writer = Default::default();
+    let q = reader.usize();
+    for _ in 0..q {
+        let query: Query = reader.next();
+        let sum: i64 = query.values.iter().sum();
+        writer.print(&query.name);
+        writer.print(" ");
+        writer.print(query.origin.0 + query.origin.1);
+        writer.print(" ");
+        writer.print(sum);
+        writer.print(" ");
+        writer.println(query.visited as u8);
+    }
+}
@@ -0,0 +1,13 @@
+// The input is exactly one page long and ends without a newline,
+// so that the last integer is read right up to the end of the mapping.
+use basm::platform::io::*;
+pub fn main() {
+    let mut reader = MmapReader::new();
+    let mut writer: Writer = Default::default();
+    let n = reader.usize();
+    let mut sum = 0i64;
+    for _ in 0..n {
+        sum += reader.i64();
+    }
+    writer.println(sum);
+}